@@ -2,18 +2,22 @@
 // SPDX-License-Identifier: Apache-2.0
 
 //! Evaluate pipeline functions.
-use anyhow::{anyhow, bail, Result};
 use polars::prelude::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 
-use crate::{completions::Completions, config::FormatConfig, parser::Expr};
+use crate::error::{bail, err, Error, Result};
+use crate::{completions::Completions, config::FormatConfig, fuzzy, parser::Expr};
 
 mod args;
 mod arrange;
+mod bind;
 mod config;
 mod count;
 mod csv;
 mod distinct;
+mod drop_nulls;
+mod expression;
 mod filter;
 mod fmt;
 mod glimpse;
@@ -23,11 +27,18 @@ mod joins;
 mod json;
 mod mutate;
 mod parquet;
+mod pivot;
 mod relocate;
 mod rename;
 mod select;
+mod separate;
+mod setops;
 mod show;
+mod slice;
 mod summarize;
+mod types;
+mod ungroup;
+mod unite;
 mod unnest;
 
 #[derive(Default)]
@@ -38,6 +49,8 @@ pub struct Context {
     df: Option<LazyFrame>,
     /// Group passed to aggregate functions.
     group: Option<LazyGroupBy>,
+    /// Columns of the active group.
+    group_keys: Vec<String>,
     /// Dataframe columns.
     columns: Vec<String>,
     /// Optional output used for testing.
@@ -46,6 +59,14 @@ pub struct Context {
     format_config: FormatConfig,
     /// Completions lru
     completions: Completions,
+    /// Build pipelines plans without reading or writing data.
+    dry_run: bool,
+    /// Dataframes written to files during a dry run.
+    dry_run_files: HashMap<PathBuf, LazyFrame>,
+    /// Set during a dry run by steps whose output columns depend on the data.
+    unknown_schema: bool,
+    /// Variables and files produced by pipelines that were not checked.
+    unknown_outputs: HashSet<String>,
 }
 
 impl Context {
@@ -68,6 +89,7 @@ impl Context {
     fn clear(&mut self) {
         self.df = None;
         self.group = None;
+        self.unknown_schema = false;
     }
 
     /// Returns and consume the input dataframe.
@@ -81,7 +103,7 @@ impl Context {
 
         self.columns = df
             .schema()
-            .map_err(|e| anyhow!("Schema error: {e}"))?
+            .map_err(schema_error)?
             .iter_names()
             .map(|s| s.to_string())
             .collect::<Vec<_>>();
@@ -107,14 +129,40 @@ impl Context {
         self.group.is_some()
     }
 
-    /// Sets the active group.
-    fn set_group(&mut self, group: LazyGroupBy) -> Result<()> {
+    /// Returns and consume the active group input dataframe and group columns.
+    fn take_group_df(&mut self) -> Option<(LazyFrame, Vec<String>)> {
+        let group = self.group.take()?;
+        Some((LazyFrame::from(group), std::mem::take(&mut self.group_keys)))
+    }
+
+    /// Returns and consume the input dataframe, or the active group input
+    /// dataframe and group columns.
+    fn take_df_or_group(&mut self) -> Option<(LazyFrame, Option<Vec<String>>)> {
+        if let Some(df) = self.take_df() {
+            Some((df, None))
+        } else {
+            self.take_group_df().map(|(df, keys)| (df, Some(keys)))
+        }
+    }
+
+    /// Sets the dataframe, or groups it again if it has group columns.
+    fn set_df_or_group(&mut self, df: LazyFrame, keys: Option<Vec<String>>) -> Result<()> {
+        if let Some(keys) = keys {
+            let partition = keys.iter().map(|k| col(k)).collect::<Vec<_>>();
+            self.set_group(df.group_by_stable(&partition), keys)
+        } else {
+            self.set_df(df)
+        }
+    }
+
+    /// Sets the active group and its columns.
+    fn set_group(&mut self, group: LazyGroupBy, keys: Vec<String>) -> Result<()> {
         assert!(self.df.is_none());
 
         self.columns = group
             .logical_plan
             .compute_schema()
-            .map_err(|e| anyhow!("Schema error: {e}"))?
+            .map_err(schema_error)?
             .iter_names()
             .map(|s| s.to_string())
             .collect::<Vec<_>>();
@@ -122,6 +170,7 @@ impl Context {
         self.update_completions();
 
         self.group = Some(group);
+        self.group_keys = keys;
         Ok(())
     }
 
@@ -129,8 +178,72 @@ impl Context {
         self.completions.add(&self.columns);
     }
 
+    /// Returns true if pipelines are only checked without reading any data.
+    fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Records a dataframe written to a file during a dry run.
+    fn dry_run_write(&mut self, path: &Path, df: LazyFrame) -> Result<()> {
+        self.dry_run_files.insert(path.to_path_buf(), df.clone());
+        self.set_df(df)
+    }
+
+    /// Marks the output schema as unknown, remaining steps are not checked.
+    fn set_unknown_schema(&mut self) {
+        self.unknown_schema = true;
+    }
+
+    /// Records the variables and files produced by steps that are not checked.
+    fn skip_steps(&mut self, exprs: &[Expr]) {
+        for expr in exprs {
+            match expr {
+                Expr::Identifier(name) => {
+                    self.unknown_outputs.insert(name.to_owned());
+                }
+                Expr::Function(name, args)
+                    if matches!(name.as_str(), "csv" | "json" | "parquet") =>
+                {
+                    if let Some(Expr::String(path)) = args.first() {
+                        self.unknown_outputs.insert(path.to_owned());
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Returns true if the expression uses a variable or a file produced by
+    /// steps that were not checked.
+    fn uses_unknown(&self, expr: &Expr) -> bool {
+        match expr {
+            Expr::Identifier(name) => {
+                // Columns of another dataframe are referenced as `df.column`.
+                let var = name.split_once('.').map_or(name.as_str(), |(var, _)| var);
+                self.unknown_outputs.contains(name) || self.unknown_outputs.contains(var)
+            }
+            Expr::String(path) => self.unknown_outputs.contains(path),
+            Expr::Function(_, args) | Expr::List(args) => {
+                args.iter().any(|arg| self.uses_unknown(arg))
+            }
+            Expr::BinaryOp(lhs, _, rhs) => self.uses_unknown(lhs) || self.uses_unknown(rhs),
+            Expr::UnaryOp(_, expr) => self.uses_unknown(expr),
+            _ => false,
+        }
+    }
+
+    /// Returns the dataframe written to a file by a previous dry run step.
+    fn dry_run_read(&self, path: &Path) -> Option<LazyFrame> {
+        self.dry_run_files.get(path).cloned()
+    }
+
     /// Print results to the context output.
-    fn print(&mut self, df: DataFrame) -> Result<()> {
+    fn print(&mut self, df: LazyFrame) -> Result<()> {
+        if self.dry_run {
+            return Ok(());
+        }
+
+        let df = df.collect()?;
         self.set_fmt();
 
         if let Some(write) = self.output.as_mut() {
@@ -143,6 +256,10 @@ impl Context {
 
     /// Show a glimpse view of the datafrmae.
     fn glimpse(&mut self, df: LazyFrame) -> Result<()> {
+        if self.dry_run {
+            return Ok(());
+        }
+
         self.set_fmt();
 
         if let Some(write) = self.output.as_mut() {
@@ -173,6 +290,8 @@ impl Context {
 
 /// Evaluate pipelines expressions to standard output.
 pub fn eval(ctx: &mut Context, exprs: &[Expr]) -> Result<()> {
+    check_pipelines(exprs, ctx)?;
+
     // Let the interpreters handle the number of rows in the output.
     eval_pipelines(exprs, ctx)
 }
@@ -188,18 +307,47 @@ pub fn eval_to_string(exprs: &[Expr]) -> Result<String> {
         ..Default::default()
     };
 
+    check_pipelines(exprs, &ctx)?;
     eval_pipelines(exprs, &mut ctx)?;
 
-    Ok(String::from_utf8(ctx.output.unwrap())?)
+    let output = ctx.output.unwrap_or_default();
+    String::from_utf8(output).map_err(|e| err!(Io, "Invalid output: {e}"))
+}
+
+/// Checks columns and types for all pipelines before any data is read.
+///
+/// The pipelines are evaluated on a copy of the context that only builds the
+/// lazy plans, so that schema errors are reported for the whole script.
+fn check_pipelines(exprs: &[Expr], ctx: &Context) -> Result<()> {
+    let mut check_ctx = Context {
+        vars: ctx.vars.clone(),
+        dry_run: true,
+        ..Default::default()
+    };
+
+    eval_pipelines(exprs, &mut check_ctx)
 }
 
 fn eval_pipelines(exprs: &[Expr], ctx: &mut Context) -> Result<()> {
     for expr in exprs {
-        if let Expr::Pipeline(exprs) = expr {
+        if let Expr::Pipeline(exprs, locations) = expr {
             ctx.clear();
 
-            for expr in exprs {
-                eval_pipeline_step(expr, ctx)?;
+            // Pipelines that use the output of unchecked steps are checked when
+            // the data is read.
+            if exprs.iter().any(|expr| ctx.uses_unknown(expr)) {
+                ctx.skip_steps(exprs);
+                continue;
+            }
+
+            for (idx, (expr, location)) in exprs.iter().zip(locations).enumerate() {
+                eval_pipeline_step(expr, ctx).map_err(|e| e.at(*location, expr.function_name()))?;
+
+                // Steps after a pivot_wider are checked when the data is read.
+                if ctx.unknown_schema {
+                    ctx.skip_steps(&exprs[idx + 1..]);
+                    break;
+                }
             }
         }
     }
@@ -212,28 +360,47 @@ fn eval_pipeline_step(expr: &Expr, ctx: &mut Context) -> Result<()> {
         Expr::Function(name, args) => match name.as_str() {
             "anti_join" => joins::eval(args, ctx, JoinType::Anti)?,
             "arrange" => arrange::eval(args, ctx)?,
+            "asof_join" => joins::eval_asof(args, ctx)?,
+            "bind_cols" => bind::eval_cols(args, ctx)?,
+            "bind_rows" => bind::eval_rows(args, ctx)?,
             "config" => config::eval(args, ctx)?,
             "count" => count::eval(args, ctx)?,
             "cross_join" => joins::eval(args, ctx, JoinType::Cross)?,
             "csv" => csv::eval(args, ctx)?,
             "distinct" => distinct::eval(args, ctx)?,
+            "drop_nulls" => drop_nulls::eval(args, ctx)?,
             "filter" => filter::eval(args, ctx)?,
             "glimpse" => glimpse::eval(args, ctx)?,
             "group_by" => group_by::eval(args, ctx)?,
             "head" => head::eval(args, ctx)?,
             "inner_join" => joins::eval(args, ctx, JoinType::Inner)?,
+            "intersect" => setops::eval_intersect(args, ctx)?,
             "json" => json::eval(args, ctx)?,
             "left_join" => joins::eval(args, ctx, JoinType::Left)?,
             "mutate" => mutate::eval(args, ctx)?,
             "outer_join" => joins::eval(args, ctx, JoinType::Outer)?,
             "parquet" => parquet::eval(args, ctx)?,
+            "pivot_longer" => pivot::eval_longer(args, ctx)?,
+            "pivot_wider" => pivot::eval_wider(args, ctx)?,
             "relocate" => relocate::eval(args, ctx)?,
             "rename" => rename::eval(args, ctx)?,
             "select" => select::eval(args, ctx)?,
+            "semi_join" => joins::eval(args, ctx, JoinType::Semi)?,
+            "separate" => separate::eval(args, ctx)?,
+            "setdiff" => setops::eval_setdiff(args, ctx)?,
             "show" => show::eval(args, ctx)?,
+            "slice" => slice::eval_slice(args, ctx)?,
+            "slice_max" => slice::eval_slice_ordered(args, ctx, true)?,
+            "slice_min" => slice::eval_slice_ordered(args, ctx, false)?,
+            "slice_sample" => slice::eval_slice_sample(args, ctx)?,
             "summarize" => summarize::eval(args, ctx)?,
+            "tail" => slice::eval_tail(args, ctx)?,
+            "ungroup" => ungroup::eval(args, ctx)?,
+            "unite" => unite::eval(args, ctx)?,
+            "union" => setops::eval_union(args, ctx, true)?,
+            "union_all" => setops::eval_union(args, ctx, false)?,
             "unnest" => unnest::eval(args, ctx)?,
-            _ => panic!("Unknown function {name}"),
+            _ => bail!(Type, "Unknown function {name}"),
         },
         Expr::Identifier(name) => {
             // If there is an input assign it to the variable.
@@ -243,13 +410,23 @@ fn eval_pipeline_step(expr: &Expr, ctx: &mut Context) -> Result<()> {
             } else if let Some(plan) = ctx.vars.get(name) {
                 ctx.set_df(plan.clone())?;
             } else if ctx.is_grouping() {
-                bail!("Cannot assign a group to variable '{name}'");
+                bail!(Type, "Cannot assign a group to variable '{name}'");
             } else {
-                bail!("Undefined variable '{name}'");
+                let hint = fuzzy::did_you_mean(name, ctx.vars.keys().map(|v| v.as_str()));
+                bail!(Schema, "Undefined variable '{name}'{hint}");
             }
         }
-        _ => panic!("Unexpected expression {expr}"),
+        _ => bail!(Type, "Unexpected expression {expr}"),
     }
 
     Ok(())
 }
+
+/// Converts an error computing the dataframe schema, a file that cannot be
+/// read is reported as is.
+fn schema_error(e: PolarsError) -> Error {
+    match Error::from_schema(e) {
+        e @ Error::Io(_) => e,
+        e => e.context("Schema error"),
+    }
+}
@@ -1,49 +1,62 @@
 // Copyright (C) 2023 Vince Vasta
 // SPDX-License-Identifier: Apache-2.0
-use anyhow::{anyhow, bail, Result};
 use polars::export::chrono::prelude::*;
 use polars::lazy::dsl::Expr as PolarsExpr;
 use polars::prelude::*;
 use std::str::FromStr;
 
+use crate::error::{bail, err, Result};
+use crate::fuzzy;
 use crate::parser::{Expr, Operator};
 
 /// Returns the string from a string expression.
 ///
-/// Panics if the expression is not a string.
-pub fn string(expr: &Expr) -> String {
+/// Returns an error if the expression is not a string.
+pub fn string(expr: &Expr) -> Result<String> {
     match expr {
-        Expr::String(s) => s.to_owned(),
-        _ => panic!("{expr} is not a string expression"),
+        Expr::String(s) => Ok(s.to_owned()),
+        _ => Err(err!(Type, "{expr} is not a string expression")),
     }
 }
 
 /// Returns the string from an identifier expression.
 ///
-/// Panics if the expression is not an identifier.
-pub fn identifier(expr: &Expr) -> String {
+/// Returns an error if the expression is not an identifier.
+pub fn identifier(expr: &Expr) -> Result<String> {
     match expr {
-        Expr::Identifier(s) => s.to_owned(),
-        _ => panic!("{expr} is not an identifier expression"),
+        Expr::Identifier(s) => Ok(s.to_owned()),
+        _ => Err(err!(Type, "{expr} is not an identifier expression")),
     }
 }
 
 /// Returns a Polars column if it is in the schema.
 pub fn column(expr: &Expr, schema: &Schema) -> Result<PolarsExpr> {
-    let column = identifier(expr);
-    schema
-        .get(&column)
-        .map(|_| col(&column))
-        .ok_or_else(|| anyhow!("Unknown column '{expr}'"))
+    let column = identifier(expr)?;
+    schema.get(&column).map(|_| col(&column)).ok_or_else(|| {
+        let hint = fuzzy::did_you_mean(&column, schema.iter_names().map(|c| c.as_str()));
+        err!(Schema, "Unknown column '{expr}'{hint}")
+    })
 }
 
 /// Returns the value from a number expression.
 ///
-/// Panics if the expression is not a number.
-pub fn number(expr: &Expr) -> f64 {
+/// Returns an error if the expression is not a number.
+pub fn number(expr: &Expr) -> Result<f64> {
+    expr.number()
+        .ok_or_else(|| err!(Type, "{expr} is not a number expression"))
+}
+
+/// Returns the value of a bool argument like `remove = true`.
+///
+/// Returns an error if the expression is not `true` or `false`.
+pub fn bool(name: &str, expr: &Expr) -> Result<bool> {
     match expr {
-        Expr::Number(s) => *s,
-        _ => panic!("{expr} is not a number expression"),
+        Expr::Identifier(s) if s == "true" => Ok(true),
+        Expr::Identifier(s) if s == "false" => Ok(false),
+        _ => Err(err!(
+            Type,
+            "invalid {name} value '{expr}', expected true or false"
+        )),
     }
 }
 
@@ -51,7 +64,7 @@ pub fn number(expr: &Expr) -> f64 {
 ///
 /// Returns an error if the string is not a valid date time.
 pub fn timestamp(expr: &Expr) -> Result<NaiveDateTime> {
-    let ts = string(expr);
+    let ts = string(expr)?;
     let ts = ts.trim();
 
     let dt = NaiveDateTime::parse_from_str(ts, "%Y-%m-%d %H:%M:%S%.f")
@@ -60,7 +73,7 @@ pub fn timestamp(expr: &Expr) -> Result<NaiveDateTime> {
             NaiveDate::parse_from_str(ts, "%Y-%m-%d")
                 .map(|d| NaiveDateTime::new(d, Default::default()))
         })
-        .map_err(|e| anyhow!("Invalid timestamp string {ts}: {e}"))?;
+        .map_err(|e| err!(Type, "Invalid timestamp string {ts}: {e}"))?;
 
     Ok(dt)
 }
@@ -70,7 +83,8 @@ pub fn named_bool(args: &[Expr], name: &str) -> Result<bool> {
         if let Expr::BinaryOp(lhs, Operator::Assign, rhs) = arg {
             match (lhs.as_ref(), rhs.as_ref()) {
                 (Expr::Identifier(lhs), Expr::Identifier(rhs)) if lhs == name => {
-                    return Ok(bool::from_str(rhs)?);
+                    return bool::from_str(rhs)
+                        .map_err(|e| err!(Type, "invalid {name} value '{rhs}': {e}"));
                 }
                 _ => {}
             }
@@ -84,12 +98,12 @@ pub fn named_bool(args: &[Expr], name: &str) -> Result<bool> {
 pub fn named_usize(args: &[Expr], name: &str) -> Result<Option<usize>> {
     for arg in args {
         if let Expr::BinaryOp(lhs, Operator::Assign, rhs) = arg {
-            match (lhs.as_ref(), rhs.as_ref()) {
-                (Expr::Identifier(lhs), Expr::Number(value)) if lhs == name => {
-                    if *value >= 0.0 {
-                        return Ok(Some(*value as usize));
+            match (lhs.as_ref(), rhs.number()) {
+                (Expr::Identifier(lhs), Some(value)) if lhs == name => {
+                    if value >= 0.0 {
+                        return Ok(Some(value as usize));
                     } else {
-                        bail!("{name} must have positive value");
+                        bail!(Type, "{name} must have positive value");
                     }
                 }
                 _ => {}
@@ -1,8 +1,8 @@
 // Copyright (C) 2023 Vince Vasta
 // SPDX-License-Identifier: Apache-2.0
-use anyhow::{bail, Result};
 use polars::prelude::*;
 
+use crate::error::{bail, Result};
 use crate::parser::Expr;
 
 use super::*;
@@ -11,7 +11,7 @@ use super::*;
 ///
 /// Parameters are checked before evaluation by the typing module.
 pub fn eval(args: &[Expr], ctx: &mut Context) -> Result<()> {
-    if let Some(df) = ctx.take_df() {
+    if let Some((df, keys)) = ctx.take_df_or_group() {
         let schema_cols = ctx.columns();
         let mut columns = Vec::with_capacity(args.len());
         let mut descending = Vec::with_capacity(args.len());
@@ -20,9 +20,11 @@ pub fn eval(args: &[Expr], ctx: &mut Context) -> Result<()> {
             match arg {
                 Expr::Function(name, args) if name == "desc" => {
                     // arrange(desc(column))
-                    let column = args::identifier(&args[0]);
+                    let column = args::identifier(&args[0])?;
                     if !schema_cols.contains(&column) {
-                        bail!("arrange error: Unknown column {column}");
+                        let hint =
+                            fuzzy::did_you_mean(&column, schema_cols.iter().map(|c| c.as_str()));
+                        bail!(Schema, "arrange error: Unknown column '{column}'{hint}");
                     }
 
                     columns.push(col(&column));
@@ -31,7 +33,9 @@ pub fn eval(args: &[Expr], ctx: &mut Context) -> Result<()> {
                 Expr::Identifier(column) => {
                     // arrange(column)
                     if !schema_cols.contains(column) {
-                        bail!("arrange error: Unknown column {column}");
+                        let hint =
+                            fuzzy::did_you_mean(column, schema_cols.iter().map(|c| c.as_str()));
+                        bail!(Schema, "arrange error: Unknown column '{column}'{hint}");
                     }
 
                     columns.push(col(column));
@@ -47,11 +51,9 @@ pub fn eval(args: &[Expr], ctx: &mut Context) -> Result<()> {
             ..Default::default()
         };
 
-        ctx.set_df(df.sort_by_exprs(columns, sort_opts))?;
-    } else if ctx.is_grouping() {
-        bail!("arrange error: must call summarize after a group_by");
+        ctx.set_df_or_group(df.sort_by_exprs(columns, sort_opts), keys)?;
     } else {
-        bail!("arrange error: missing input dataframe");
+        bail!(Type, "arrange error: missing input dataframe");
     }
 
     Ok(())
@@ -0,0 +1,155 @@
+// Copyright (C) 2023 Vince Vasta
+// SPDX-License-Identifier: Apache-2.0
+use polars::prelude::*;
+
+use crate::error::{bail, Result};
+use crate::parser::{Expr, Operator};
+
+use super::*;
+
+/// Evaluates a bind_rows call.
+///
+/// Columns are matched by name, columns missing from a dataframe are filled
+/// with nulls.
+pub fn eval_rows(args: &[Expr], ctx: &mut Context) -> Result<()> {
+    if let Some(df) = ctx.take_df() {
+        let mut dfs = vec![df];
+        let mut id = None;
+
+        for arg in args {
+            match arg {
+                Expr::BinaryOp(lhs, Operator::Assign, rhs) => {
+                    if args::identifier(lhs)? == "id" {
+                        id = Some(args::identifier(rhs)?);
+                    }
+                }
+                _ => dfs.push(get_df("bind_rows", arg, ctx)?),
+            }
+        }
+
+        let mut combined = schema("bind_rows", &dfs[0])?;
+        for df in &dfs[1..] {
+            for (name, dtype) in schema("bind_rows", df)?.iter() {
+                match combined.get(name) {
+                    Some(other) if !compatible_types(other, dtype) => bail!(
+                        Schema,
+                        "bind_rows error: column '{name}' has incompatible types {other} and {dtype}"
+                    ),
+                    Some(_) => {}
+                    None => {
+                        combined.with_column(name.clone(), dtype.clone());
+                    }
+                }
+            }
+        }
+
+        // Add the position of the source dataframe as first column.
+        if let Some(id) = id {
+            dfs = dfs
+                .into_iter()
+                .enumerate()
+                .map(|(idx, df)| {
+                    let source = lit(idx as u32 + 1).cast(DataType::UInt32);
+                    df.select([source.alias(&id), all()])
+                })
+                .collect();
+        }
+
+        let args = UnionArgs {
+            to_supertypes: true,
+            ..Default::default()
+        };
+        let df =
+            concat_lf_diagonal(dfs, args).map_err(|e| Error::from(e).context("bind_rows error"))?;
+
+        ctx.set_df(df)?;
+    } else if ctx.is_grouping() {
+        bail!(
+            Type,
+            "bind_rows error: must call summarize or ungroup after a group_by"
+        );
+    } else {
+        bail!(Type, "bind_rows error: missing input dataframe");
+    }
+
+    Ok(())
+}
+
+/// Evaluates a bind_cols call.
+///
+/// All dataframes must have the same number of rows and distinct column names.
+pub fn eval_cols(args: &[Expr], ctx: &mut Context) -> Result<()> {
+    if let Some(df) = ctx.take_df() {
+        let mut columns = ctx.columns().clone();
+        let mut dfs = vec![df];
+
+        for arg in args {
+            let df = get_df("bind_cols", arg, ctx)?;
+            let schema = schema("bind_cols", &df)?;
+            for column in schema.iter_names() {
+                if columns.iter().any(|c| c == column.as_str()) {
+                    bail!(Schema, "bind_cols error: duplicate column '{column}'");
+                }
+                columns.push(column.to_string());
+            }
+
+            dfs.push(df);
+        }
+
+        if ctx.is_dry_run() {
+            let df = concat_lf_horizontal(dfs, Default::default())?;
+            return ctx.set_df(df);
+        }
+
+        let dfs = dfs
+            .into_iter()
+            .map(|df| df.collect())
+            .collect::<PolarsResult<Vec<_>>>()?;
+
+        let height = dfs[0].height();
+        if let Some(df) = dfs.iter().find(|df| df.height() != height) {
+            bail!(
+                Engine,
+                "bind_cols error: dataframes have different number of rows {height} and {}",
+                df.height()
+            );
+        }
+
+        let df = polars::functions::concat_df_horizontal(&dfs)?;
+        ctx.set_df(df.lazy())?;
+    } else if ctx.is_grouping() {
+        bail!(
+            Type,
+            "bind_cols error: must call summarize or ungroup after a group_by"
+        );
+    } else {
+        bail!(Type, "bind_cols error: missing input dataframe");
+    }
+
+    Ok(())
+}
+
+/// Returns true if two columns types can be combined.
+pub fn compatible_types(lhs: &DataType, rhs: &DataType) -> bool {
+    lhs == rhs || (lhs.is_numeric() && rhs.is_numeric()) || lhs.is_null() || rhs.is_null()
+}
+
+fn schema(fname: &str, df: &LazyFrame) -> Result<Schema> {
+    df.schema()
+        .map(|schema| schema.as_ref().clone())
+        .map_err(|e| Error::from_schema(e).context(&format!("{fname} error")))
+}
+
+/// Returns the dataframe for a variable argument.
+fn get_df(fname: &str, arg: &Expr, ctx: &Context) -> Result<LazyFrame> {
+    let name = args::identifier(arg)?;
+    if let Some(df) = ctx.get_df(&name) {
+        Ok(df.clone())
+    } else {
+        let hint = fuzzy::did_you_mean(&name, ctx.vars.keys().map(|v| v.as_str()));
+        bail!(
+            Schema,
+            "{fname} error: undefined dataframe variable '{name}'{hint}"
+        );
+    }
+}
@@ -1,8 +1,8 @@
 // Copyright (C) 2023 Vince Vasta
 // SPDX-License-Identifier: Apache-2.0
-use anyhow::{bail, Result};
 use polars::prelude::*;
 
+use crate::error::{bail, Result};
 use crate::parser::Expr;
 
 use super::*;
@@ -18,7 +18,8 @@ pub fn eval(args: &[Expr], ctx: &mut Context) -> Result<()> {
         for arg in args {
             if let Expr::Identifier(column) = arg {
                 if !schema_cols.contains(column) {
-                    bail!("count error: Unknown column {column}");
+                    let hint = fuzzy::did_you_mean(column, schema_cols.iter().map(|c| c.as_str()));
+                    bail!(Schema, "count error: Unknown column '{column}'{hint}");
                 }
 
                 let expr = col(column);
@@ -55,9 +56,12 @@ pub fn eval(args: &[Expr], ctx: &mut Context) -> Result<()> {
 
         ctx.set_df(df)?;
     } else if ctx.is_grouping() {
-        bail!("count error: must call summarize after a group_by");
+        bail!(
+            Type,
+            "count error: must call summarize or ungroup after a group_by"
+        );
     } else {
-        bail!("count error: missing input dataframe");
+        bail!(Type, "count error: missing input dataframe");
     }
 
     Ok(())
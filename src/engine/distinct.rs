@@ -1,8 +1,8 @@
 // Copyright (C) 2023 Vince Vasta
 // SPDX-License-Identifier: Apache-2.0
-use anyhow::{bail, Result};
 use polars::prelude::*;
 
+use crate::error::{bail, Result};
 use crate::parser::Expr;
 
 use super::*;
@@ -11,14 +11,15 @@ use super::*;
 ///
 /// Parameters are checked before evaluation by the typing module.
 pub fn eval(args: &[Expr], ctx: &mut Context) -> Result<()> {
-    if let Some(df) = ctx.take_df() {
+    if let Some((df, keys)) = ctx.take_df_or_group() {
         let schema_cols = ctx.columns();
         let mut select_columns = Vec::new();
 
         for arg in args {
-            let column = args::identifier(arg);
+            let column = args::identifier(arg)?;
             if !schema_cols.contains(&column) {
-                bail!("distinct error: Unknown column {column}");
+                let hint = fuzzy::did_you_mean(&column, schema_cols.iter().map(|c| c.as_str()));
+                bail!(Schema, "distinct error: Unknown column '{column}'{hint}");
             }
 
             if !select_columns.contains(&column) {
@@ -27,6 +28,13 @@ pub fn eval(args: &[Expr], ctx: &mut Context) -> Result<()> {
         }
 
         let df = if !select_columns.is_empty() {
+            // Group columns are always kept.
+            for key in keys.iter().flatten().rev() {
+                if !select_columns.contains(key) {
+                    select_columns.insert(0, key.to_owned());
+                }
+            }
+
             let columns = select_columns.iter().map(|c| col(c)).collect::<Vec<_>>();
             df.select(&columns)
                 .unique_stable(Some(select_columns), UniqueKeepStrategy::First)
@@ -34,11 +42,9 @@ pub fn eval(args: &[Expr], ctx: &mut Context) -> Result<()> {
             df.unique_stable(None, UniqueKeepStrategy::First)
         };
 
-        ctx.set_df(df)?;
-    } else if ctx.is_grouping() {
-        bail!("distinct error: must call summarize after a group_by");
+        ctx.set_df_or_group(df, keys)?;
     } else {
-        bail!("distinct error: missing input dataframe");
+        bail!(Type, "distinct error: missing input dataframe");
     }
 
     Ok(())
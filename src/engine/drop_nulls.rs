@@ -0,0 +1,40 @@
+// Copyright (C) 2023 Vince Vasta
+// SPDX-License-Identifier: Apache-2.0
+use polars::prelude::*;
+
+use crate::error::{bail, Result};
+use crate::parser::Expr;
+
+use super::*;
+
+/// Evaluates a drop_nulls call.
+///
+/// Parameters are checked before evaluation by the typing module.
+pub fn eval(args: &[Expr], ctx: &mut Context) -> Result<()> {
+    if let Some((df, keys)) = ctx.take_df_or_group() {
+        let schema_cols = ctx.columns();
+        let mut subset = Vec::new();
+
+        for arg in args {
+            let column = args::identifier(arg)?;
+            if !schema_cols.contains(&column) {
+                let hint = fuzzy::did_you_mean(&column, schema_cols.iter().map(|c| c.as_str()));
+                bail!(Schema, "drop_nulls error: Unknown column '{column}'{hint}");
+            }
+
+            subset.push(col(&column));
+        }
+
+        let subset = if subset.is_empty() {
+            None
+        } else {
+            Some(subset)
+        };
+
+        ctx.set_df_or_group(df.drop_nulls(subset), keys)?;
+    } else {
+        bail!(Type, "drop_nulls error: missing input dataframe");
+    }
+
+    Ok(())
+}
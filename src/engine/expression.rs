@@ -0,0 +1,1065 @@
+// Copyright (C) 2023 Vince Vasta
+// SPDX-License-Identifier: Apache-2.0
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Evaluation of the expressions shared by mutate, filter, and conditionals.
+use polars::export::regex;
+use polars::lazy::dsl::{duration, DurationArgs, Expr as PolarsExpr, StrptimeOptions};
+use polars::prelude::*;
+use polars::series::ops::NullBehavior;
+
+use crate::error::{bail, Result};
+use crate::parser::{Expr, Operator};
+
+use super::*;
+
+/// Evaluates an expression used by mutate, filter and conditionals.
+pub fn eval(expr: &Expr, schema: &Schema) -> Result<PolarsExpr> {
+    match expr {
+        Expr::BinaryOp(lhs, Operator::In, rhs) => is_in(lhs, rhs, schema),
+        Expr::BinaryOp(lhs, op @ (Operator::And | Operator::Or), rhs) => {
+            let lhs_expr = eval(lhs, schema)?;
+            let rhs_expr = eval(rhs, schema)?;
+
+            if let Operator::And = op {
+                Ok(lhs_expr.and(rhs_expr))
+            } else {
+                Ok(lhs_expr.or(rhs_expr))
+            }
+        }
+        Expr::BinaryOp(
+            lhs,
+            op @ (Operator::Eq
+            | Operator::NotEq
+            | Operator::Lt
+            | Operator::LtEq
+            | Operator::Gt
+            | Operator::GtEq),
+            rhs,
+        ) => {
+            let lhs_expr = eval(lhs, schema)?;
+            let rhs_expr = eval(rhs, schema)?;
+            types::check_compare(
+                (lhs, &types::dtype(&lhs_expr, schema)?),
+                (rhs, &types::dtype(&rhs_expr, schema)?),
+            )?;
+
+            let result = match op {
+                Operator::Eq => lhs_expr.eq(rhs_expr),
+                Operator::NotEq => lhs_expr.neq(rhs_expr),
+                Operator::Lt => lhs_expr.lt(rhs_expr),
+                Operator::LtEq => lhs_expr.lt_eq(rhs_expr),
+                Operator::Gt => lhs_expr.gt(rhs_expr),
+                _ => lhs_expr.gt_eq(rhs_expr),
+            };
+
+            Ok(result)
+        }
+        Expr::BinaryOp(lhs, op, rhs) => {
+            let lhs_expr = eval(lhs, schema)?;
+            let rhs_expr = eval(rhs, schema)?;
+            types::check_arith(
+                *op,
+                (lhs, &types::dtype(&lhs_expr, schema)?),
+                (rhs, &types::dtype(&rhs_expr, schema)?),
+            )?;
+
+            let result = match op {
+                Operator::Plus => lhs_expr + rhs_expr,
+                Operator::Minus => lhs_expr - rhs_expr,
+                Operator::Multiply => lhs_expr * rhs_expr,
+                Operator::Divide => lhs_expr / rhs_expr,
+                Operator::Mod => lhs_expr % rhs_expr.cast(DataType::UInt64),
+                Operator::Pow => lhs_expr.cast(DataType::Float64).pow(rhs_expr),
+                _ => bail!(Type, "Unexpected operator {op}"),
+            };
+
+            Ok(result)
+        }
+        Expr::Identifier(_) => args::column(expr, schema),
+        Expr::String(s) => Ok(lit(s.clone())),
+        Expr::Number(n) => Ok(lit(*n)),
+        Expr::UnaryOp(Operator::Not, expr) => eval(expr, schema).map(|expr| expr.not()),
+        Expr::UnaryOp(Operator::Minus, expr) => negate(expr, eval(expr, schema)?, schema),
+        Expr::Function(name, args) if name == "dt" => {
+            let ts = args::timestamp(&args[0])?;
+            Ok(lit(ts))
+        }
+        Expr::Function(name, args) if name == "contains" => eval_contains(args, schema),
+        Expr::Function(name, args) if name == "starts_with" => {
+            let expr = eval_string(name, &args[0], schema)?;
+            Ok(expr.str().starts_with(lit(args::string(&args[1])?)))
+        }
+        Expr::Function(name, args) if name == "ends_with" => {
+            let expr = eval_string(name, &args[0], schema)?;
+            Ok(expr.str().ends_with(lit(args::string(&args[1])?)))
+        }
+        Expr::Function(name, args) if name == "matches" => {
+            let expr = eval_string(name, &args[0], schema)?;
+            let (pattern, ignore_case) = pattern_arg(name, &args[1..], "ignore_case")?;
+            let flags = if ignore_case { "(?i)" } else { "" };
+            let pattern = Expr::String(format!("{flags}{}", args::string(pattern)?));
+            Ok(expr.str().contains(regex(name, &pattern)?, false))
+        }
+        Expr::Function(name, args) if name == "str_equals_ci" => {
+            let expr = eval_string(name, &args[0], schema)?;
+            let value = args::string(&args[1])?.to_lowercase();
+            Ok(expr.str().to_lowercase().eq(lit(value)))
+        }
+        Expr::Function(name, args) if name == "is_null" => {
+            eval(&args[0], schema).map(|c| c.is_null())
+        }
+        Expr::Function(name, args) if name == "is_in" => is_in(&args[0], &args[1], schema),
+        Expr::Function(name, args) if name == "between" => eval_between(args, schema),
+        Expr::Function(name, args) if name == "ymd_hms" => {
+            check_column(name, &args[0], schema, "a string", |dt| dt.is_string())?;
+            args::column(&args[0], schema).map(|c| {
+                c.str().to_datetime(
+                    Some(TimeUnit::Nanoseconds),
+                    None,
+                    StrptimeOptions::default(),
+                    lit("raise"),
+                )
+            })
+        }
+        Expr::Function(name, args) if name == "dnanos" => {
+            check_column(name, &args[0], schema, "numeric", |dt| dt.is_numeric())?;
+            args::column(&args[0], schema).map(|c| {
+                duration(DurationArgs {
+                    nanoseconds: c,
+                    ..Default::default()
+                })
+            })
+        }
+        Expr::Function(name, args) if name == "dmicros" => {
+            check_column(name, &args[0], schema, "numeric", |dt| dt.is_numeric())?;
+            args::column(&args[0], schema).map(|c| {
+                duration(DurationArgs {
+                    microseconds: c,
+                    ..Default::default()
+                })
+            })
+        }
+        Expr::Function(name, args) if name == "dmillis" => {
+            check_column(name, &args[0], schema, "numeric", |dt| dt.is_numeric())?;
+            args::column(&args[0], schema).map(|c| {
+                duration(DurationArgs {
+                    milliseconds: c,
+                    ..Default::default()
+                })
+            })
+        }
+        Expr::Function(name, args) if name == "dsecs" => {
+            check_column(name, &args[0], schema, "numeric", |dt| dt.is_numeric())?;
+            args::column(&args[0], schema).map(|c| {
+                duration(DurationArgs {
+                    seconds: c,
+                    ..Default::default()
+                })
+            })
+        }
+        Expr::Function(name, args) if name == "nanos" => {
+            check_column(name, &args[0], schema, "a duration", |dt| {
+                matches!(dt, DataType::Duration(_))
+            })?;
+            args::column(&args[0], schema).map(|c| c.dt().total_nanoseconds())
+        }
+        Expr::Function(name, args) if name == "micros" => {
+            check_column(name, &args[0], schema, "a duration", |dt| {
+                matches!(dt, DataType::Duration(_))
+            })?;
+            args::column(&args[0], schema).map(|c| c.dt().total_microseconds())
+        }
+        Expr::Function(name, args) if name == "millis" => {
+            check_column(name, &args[0], schema, "a duration", |dt| {
+                matches!(dt, DataType::Duration(_))
+            })?;
+            args::column(&args[0], schema).map(|c| c.dt().total_milliseconds())
+        }
+        Expr::Function(name, args) if name == "secs" => {
+            check_column(name, &args[0], schema, "a duration", |dt| {
+                matches!(dt, DataType::Duration(_))
+            })?;
+            args::column(&args[0], schema).map(|c| c.dt().total_seconds())
+        }
+        Expr::Function(name, args)
+            if matches!(
+                name.as_str(),
+                "year"
+                    | "quarter"
+                    | "month"
+                    | "day"
+                    | "hour"
+                    | "minute"
+                    | "second"
+                    | "weekday"
+                    | "week"
+                    | "day_of_year"
+            ) =>
+        {
+            eval_date_part(name, &args[0], schema)
+        }
+        Expr::Function(name, args) if name == "floor_date" || name == "ceil_date" => {
+            let expr = eval_temporal(name, &args[0], schema)?;
+            let every = date_unit(name, &args[1])?;
+            let floor = expr
+                .clone()
+                .dt()
+                .truncate(lit(every.clone()), String::new());
+
+            if name == "floor_date" {
+                Ok(floor)
+            } else {
+                Ok(when(floor.clone().eq(expr))
+                    .then(floor.clone())
+                    .otherwise(floor.dt().offset_by(lit(every))))
+            }
+        }
+        Expr::Function(name, args) if name == "strftime" => {
+            let expr = eval_temporal(name, &args[0], schema)?;
+            Ok(expr.dt().strftime(&args::string(&args[1])?))
+        }
+        Expr::Function(name, args) if name == "date_diff" => {
+            // date_diff(a, b, unit) is the time from a to b.
+            let start = eval_temporal(name, &args[0], schema)?;
+            let end = eval_temporal(name, &args[1], schema)?;
+            let diff = end - start;
+
+            match args::string(&args[2])?.as_str() {
+                "days" => Ok(diff.dt().total_days()),
+                "hours" => Ok(diff.dt().total_hours()),
+                "minutes" => Ok(diff.dt().total_minutes()),
+                "seconds" => Ok(diff.dt().total_seconds()),
+                "millis" => Ok(diff.dt().total_milliseconds()),
+                unit => bail!(
+                    Type,
+                    "`{name}` invalid unit '{unit}', expected days, hours, minutes, seconds, or millis"
+                ),
+            }
+        }
+        Expr::Function(name, args) if matches!(name.as_str(), "ymd" | "dmy" | "mdy") => {
+            let expr = eval_string(name, &args[0], schema)?;
+            let formats: &[&str] = match name.as_str() {
+                "ymd" => &["%Y-%m-%d", "%Y/%m/%d", "%Y%m%d"],
+                "dmy" => &["%d-%m-%Y", "%d/%m/%Y", "%d.%m.%Y"],
+                _ => &["%m-%d-%Y", "%m/%d/%Y"],
+            };
+
+            // Values that don't match any format are set to null.
+            let dates = formats
+                .iter()
+                .map(|format| {
+                    expr.clone().str().to_date(StrptimeOptions {
+                        format: Some(format.to_string()),
+                        strict: false,
+                        ..Default::default()
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            Ok(coalesce(&dates))
+        }
+        Expr::Function(name, args) if name == "field" => {
+            let field_name = args::identifier(&args[1])?;
+            check_column(name, &args[0], schema, "a struct", |dt| match dt {
+                DataType::Struct(fields) => fields.iter().any(|f| f.name() == &field_name),
+                _ => false,
+            })?;
+            args::column(&args[0], schema).map(|c| c.struct_().field_by_name(&field_name))
+        }
+        Expr::Function(name, args) if name == "mean" => {
+            check_agg(name, &args[0], schema)?;
+            args::column(&args[0], schema).map(|c| c.mean())
+        }
+        Expr::Function(name, args) if name == "median" => {
+            check_agg(name, &args[0], schema)?;
+            args::column(&args[0], schema).map(|c| c.median())
+        }
+        Expr::Function(name, args) if name == "min" => {
+            check_agg(name, &args[0], schema)?;
+            args::column(&args[0], schema).map(|c| c.min())
+        }
+        Expr::Function(name, args) if name == "max" => {
+            check_agg(name, &args[0], schema)?;
+            args::column(&args[0], schema).map(|c| c.max())
+        }
+        Expr::Function(name, args) if name == "sd" => {
+            check_agg(name, &args[0], schema)?;
+            args::column(&args[0], schema).map(|c| c.std(1))
+        }
+        Expr::Function(name, args) if name == "sum" => {
+            check_agg(name, &args[0], schema)?;
+            args::column(&args[0], schema).map(|c| c.sum())
+        }
+        Expr::Function(name, args) if name == "var" => {
+            check_agg(name, &args[0], schema)?;
+            args::column(&args[0], schema).map(|c| c.var(1))
+        }
+        Expr::Function(name, args) if name == "len" => {
+            let column = args::identifier(&args[0])?;
+            match schema.get(&column) {
+                Some(DataType::List(_)) => Ok(col(&column).list().len()),
+                Some(DataType::String) => Ok(col(&column).str().len_chars()),
+                Some(_) => Err(err!(Type, "`len` column '{column}' must be list or String")),
+                None => {
+                    let hint =
+                        fuzzy::did_you_mean(&column, schema.iter_names().map(|c| c.as_str()));
+                    Err(err!(Schema, "Unknown column '{column}'{hint}"))
+                }
+            }
+        }
+        Expr::Function(name, _args) if name == "row" => {
+            let (col_name, _) = schema
+                .get_at_index(0)
+                .ok_or_else(|| err!(Schema, "No columns found"))?;
+            Ok(col(col_name).map(
+                |s| Ok(Some(Series::from_iter(1..=(s.len() as u64)))),
+                GetOutput::from_type(DataType::UInt64),
+            ))
+        }
+        Expr::Function(name, _args) if name == "row_number" => {
+            Ok(int_range(lit(1), len() + lit(1), 1, DataType::UInt32))
+        }
+        Expr::Function(name, args) if name == "lag" || name == "lead" => {
+            let expr = eval(&args[0], schema)?;
+            let offset = match args.get(1) {
+                Some(arg) => args::number(arg)?,
+                None => 1.0,
+            };
+
+            if args.len() > 2 || offset < 0.0 || offset.fract() != 0.0 {
+                bail!(
+                    Type,
+                    "`{name}` offset must be a single non-negative integer"
+                );
+            }
+
+            let offset = offset as i64;
+            if name == "lag" {
+                Ok(expr.shift(lit(offset)))
+            } else {
+                Ok(expr.shift(lit(-offset)))
+            }
+        }
+        Expr::Function(name, args) if name == "cumsum" => {
+            eval_number(name, &args[0], schema).map(|e| e.cum_sum(false))
+        }
+        Expr::Function(name, args) if name == "cumprod" => {
+            eval_number(name, &args[0], schema).map(|e| e.cum_prod(false))
+        }
+        Expr::Function(name, args) if name == "cummax" => {
+            eval(&args[0], schema).map(|e| e.cum_max(false))
+        }
+        Expr::Function(name, args) if name == "cummin" => {
+            eval(&args[0], schema).map(|e| e.cum_min(false))
+        }
+        Expr::Function(name, args) if name == "diff" => {
+            eval_number(name, &args[0], schema).map(|e| e.diff(1, NullBehavior::Ignore))
+        }
+        Expr::Function(name, args) if name == "pct_change" => {
+            // Nulls are not filled so that they propagate to the next value.
+            let expr = eval_number(name, &args[0], schema)?.cast(DataType::Float64);
+            Ok(expr.clone() / expr.shift(lit(1)) - lit(1.0))
+        }
+        Expr::Function(name, args) if name == "rank" => {
+            let method = match args.get(1) {
+                Some(arg) => match args::string(arg)?.as_str() {
+                    "average" => RankMethod::Average,
+                    "min" => RankMethod::Min,
+                    "max" => RankMethod::Max,
+                    "dense" => RankMethod::Dense,
+                    "ordinal" => RankMethod::Ordinal,
+                    method => bail!(
+                        Type,
+                        "`rank` invalid method '{method}', must be one of \"average\", \"min\", \"max\", \"dense\", or \"ordinal\""
+                    ),
+                },
+                None => RankMethod::Average,
+            };
+
+            eval_rank(&args[0], method, schema)
+        }
+        Expr::Function(name, args) if name == "dense_rank" => {
+            eval_rank(&args[0], RankMethod::Dense, schema)
+        }
+        Expr::Function(name, args) if name == "percent_rank" => {
+            // Rescales the min rank to [0, 1], nulls don't count.
+            let rank = eval_rank(&args[0], RankMethod::Min, schema)?;
+            let count = eval(&args[0], schema)?.count();
+            Ok((rank.cast(DataType::Float64) - lit(1.0))
+                / (count.cast(DataType::Float64) - lit(1.0)))
+        }
+        Expr::Function(name, args) if name == "ntile" => eval_ntile(args, schema),
+        Expr::Function(name, args) if name == "if_else" => {
+            let condition = eval(&args[0], schema)?;
+            let then = eval(&args[1], schema)?;
+            let otherwise = eval(&args[2], schema)?;
+            Ok(when(condition).then(then).otherwise(otherwise))
+        }
+        Expr::Function(name, args) if name == "case_when" => eval_case_when(args, schema),
+        Expr::Function(name, args) if name == "to_upper" => {
+            eval_string(name, &args[0], schema).map(|e| e.str().to_uppercase())
+        }
+        Expr::Function(name, args) if name == "to_lower" => {
+            eval_string(name, &args[0], schema).map(|e| e.str().to_lowercase())
+        }
+        Expr::Function(name, args) if name == "trim" => {
+            eval_string(name, &args[0], schema).map(|e| e.str().strip_chars(lit(NULL)))
+        }
+        Expr::Function(name, args) if name == "replace" || name == "replace_all" => {
+            let expr = eval_string(name, &args[0], schema)?;
+            let pattern = regex(name, &args[1])?;
+            let value = lit(args::string(&args[2])?);
+
+            if name == "replace" {
+                Ok(expr.str().replace(pattern, value, false))
+            } else {
+                Ok(expr.str().replace_all(pattern, value, false))
+            }
+        }
+        Expr::Function(name, args) if name == "substr" => {
+            // substr(name, 1, 3) the start position is one based.
+            let expr = eval_string(name, &args[0], schema)?;
+            let start = args::number(&args[1])?;
+            let length = args::number(&args[2])?;
+            if start < 1.0 || length < 0.0 {
+                bail!(Type, "`substr` start must be >= 1 and length must be >= 0");
+            }
+
+            Ok(expr.str().slice(lit(start as i64 - 1), lit(length as u64)))
+        }
+        Expr::Function(name, args) if name == "str_pad" => eval_str_pad(args, schema),
+        Expr::Function(name, args) if name == "split" => {
+            let expr = eval_string(name, &args[0], schema)?;
+            Ok(expr.str().split(lit(args::string(&args[1])?)))
+        }
+        Expr::Function(name, args) if name == "concat" => {
+            let exprs = args
+                .iter()
+                .map(|arg| eval(arg, schema))
+                .collect::<Result<Vec<_>>>()?;
+            Ok(concat_str(exprs, "", false))
+        }
+        Expr::Function(name, args) if name == "extract" => {
+            let expr = eval_string(name, &args[0], schema)?;
+            let pattern = regex(name, &args[1])?;
+            let group = args::number(&args[2])?;
+            if group < 0.0 {
+                bail!(Type, "`extract` group must be >= 0");
+            }
+
+            Ok(expr.str().extract(pattern, group as usize))
+        }
+        Expr::Function(name, args) if name == "abs" => {
+            eval_number(name, &args[0], schema).map(|e| e.abs())
+        }
+        Expr::Function(name, args) if name == "round" => {
+            // round(x) or round(x, 2)
+            let expr = eval_number(name, &args[0], schema)?;
+            let digits = match args.get(1) {
+                Some(arg) => args::number(arg)?,
+                None => 0.0,
+            };
+
+            if args.len() > 2 || digits < 0.0 || digits.fract() != 0.0 {
+                bail!(Type, "`round` digits must be a single non-negative integer");
+            }
+
+            Ok(expr.round(digits as u32))
+        }
+        Expr::Function(name, args) if name == "floor" => {
+            eval_number(name, &args[0], schema).map(|e| e.floor())
+        }
+        Expr::Function(name, args) if name == "ceil" => {
+            eval_number(name, &args[0], schema).map(|e| e.ceil())
+        }
+        Expr::Function(name, args) if name == "sqrt" => {
+            eval_number(name, &args[0], schema).map(|e| e.sqrt())
+        }
+        Expr::Function(name, args) if name == "pow" => {
+            let base = eval_number(name, &args[0], schema)?;
+            let exponent = eval_number(name, &args[1], schema)?;
+            Ok(base.cast(DataType::Float64).pow(exponent))
+        }
+        Expr::Function(name, args) if name == "exp" => {
+            eval_number(name, &args[0], schema).map(|e| e.exp())
+        }
+        Expr::Function(name, args) if name == "log" => {
+            eval_number(name, &args[0], schema).map(|e| e.log(std::f64::consts::E))
+        }
+        Expr::Function(name, args) if name == "log10" => {
+            eval_number(name, &args[0], schema).map(|e| e.log(10.0))
+        }
+        Expr::Function(name, args) if name == "log2" => {
+            eval_number(name, &args[0], schema).map(|e| e.log(2.0))
+        }
+        Expr::Function(name, args) if name == "sign" => {
+            eval_number(name, &args[0], schema).map(|e| e.sign())
+        }
+        Expr::Function(name, args) if name == "clip" => {
+            let expr = eval_number(name, &args[0], schema)?;
+            let min = eval_number(name, &args[1], schema)?;
+            let max = eval_number(name, &args[2], schema)?;
+            Ok(expr.clip(min, max))
+        }
+        Expr::Function(name, args) if name == "sin" => {
+            eval_number(name, &args[0], schema).map(|e| e.sin())
+        }
+        Expr::Function(name, args) if name == "cos" => {
+            eval_number(name, &args[0], schema).map(|e| e.cos())
+        }
+        Expr::Function(name, args) if name == "tan" => {
+            eval_number(name, &args[0], schema).map(|e| e.tan())
+        }
+        Expr::Function(name, args) if name == "asin" => {
+            eval_number(name, &args[0], schema).map(|e| e.arcsin())
+        }
+        Expr::Function(name, args) if name == "acos" => {
+            eval_number(name, &args[0], schema).map(|e| e.arccos())
+        }
+        Expr::Function(name, args) if name == "atan" => {
+            eval_number(name, &args[0], schema).map(|e| e.arctan())
+        }
+        Expr::Function(name, args) if name == "coalesce" => {
+            let exprs = args
+                .iter()
+                .map(|arg| eval(arg, schema))
+                .collect::<Result<Vec<_>>>()?;
+            Ok(coalesce(&exprs))
+        }
+        Expr::Function(name, args) if name == "fill_null" => {
+            let expr = eval(&args[0], schema)?;
+            match &args[1] {
+                Expr::String(s) if s == "forward" => Ok(expr.forward_fill(None)),
+                Expr::String(s) if s == "backward" => Ok(expr.backward_fill(None)),
+                Expr::String(s) if s == "mean" => {
+                    let expr = eval_number(name, &args[0], schema)?;
+                    Ok(expr.clone().fill_null(expr.mean()))
+                }
+                value => Ok(expr.fill_null(eval(value, schema)?)),
+            }
+        }
+        Expr::Function(name, args) if name.starts_with("as_") => eval_cast(name, args, schema),
+        _ => Err(err!(Type, "Unexpected expression {expr}")),
+    }
+}
+
+/// Evaluates a cast like `as_int(x)` or `as_date(x, "%d/%m/%Y", strict = false)`.
+///
+/// Casts fail on values that cannot be converted unless `strict = false`, in
+/// which case these values are set to null.
+fn eval_cast(fname: &str, args: &[Expr], schema: &Schema) -> Result<PolarsExpr> {
+    let expr = eval(&args[0], schema)?;
+    let dtype = types::dtype(&expr, schema)?;
+
+    let mut strict = true;
+    let mut strings = Vec::new();
+
+    for arg in &args[1..] {
+        match arg {
+            Expr::String(s) => strings.push(s.clone()),
+            Expr::BinaryOp(lhs, Operator::Assign, rhs) if args::identifier(lhs)? == "strict" => {
+                strict = args::bool("strict", rhs)
+                    .map_err(|e| err!(Type, "`{fname}` {}", e.message()))?;
+            }
+            _ => bail!(Type, "Invalid `{fname}` argument '{arg}'"),
+        }
+    }
+
+    let mut strings = strings.into_iter();
+    let format = strings.next();
+    let time_zone = strings.next();
+    if strings.next().is_some() || (fname != "as_datetime" && time_zone.is_some()) {
+        bail!(Type, "Too many string arguments for `{fname}`");
+    } else if format.is_some() && !matches!(fname, "as_date" | "as_datetime") {
+        bail!(Type, "`{fname}` doesn't take a format argument");
+    }
+
+    let cast = |expr: PolarsExpr, dtype: DataType| {
+        if strict {
+            expr.strict_cast(dtype)
+        } else {
+            expr.cast(dtype)
+        }
+    };
+
+    let options = StrptimeOptions {
+        format,
+        strict,
+        ..Default::default()
+    };
+
+    let result = match fname {
+        "as_int" => cast(expr, DataType::Int64),
+        "as_float" => cast(expr, DataType::Float64),
+        "as_str" => cast(expr, DataType::String),
+        "as_bool" if dtype.is_string() => expr.map(
+            // Polars doesn't cast strings to booleans.
+            move |s| {
+                let values = s
+                    .str()?
+                    .into_iter()
+                    .map(|v| match v {
+                        Some(v) if v.eq_ignore_ascii_case("true") => Ok(Some(true)),
+                        Some(v) if v.eq_ignore_ascii_case("false") => Ok(Some(false)),
+                        Some(v) if strict => {
+                            polars_bail!(ComputeError: "conversion from `str` to `bool` failed for value {v:?}")
+                        }
+                        _ => Ok(None),
+                    })
+                    .collect::<PolarsResult<BooleanChunked>>()?;
+                Ok(Some(values.with_name(s.name()).into_series()))
+            },
+            GetOutput::from_type(DataType::Boolean),
+        ),
+        "as_bool" => cast(expr, DataType::Boolean),
+        "as_date" if dtype.is_string() => expr.str().to_date(options),
+        "as_date" => cast(expr, DataType::Date),
+        "as_datetime" if dtype.is_string() => expr.str().to_datetime(
+            Some(TimeUnit::Nanoseconds),
+            time_zone,
+            options,
+            lit("raise"),
+        ),
+        "as_datetime" => {
+            cast(expr, DataType::Datetime(TimeUnit::Nanoseconds, time_zone))
+        }
+        "as_category" => cast(
+            expr,
+            DataType::Categorical(None, CategoricalOrdering::Physical),
+        ),
+        _ => bail!(Type, "Unknown cast function `{fname}`"),
+    };
+
+    Ok(result)
+}
+
+/// Evaluates `ntile(x, k)` that splits the ordered values in `k` buckets.
+///
+/// Buckets sizes differ by at most one with larger buckets first like dplyr.
+fn eval_ntile(args: &[Expr], schema: &Schema) -> Result<PolarsExpr> {
+    let buckets = args::number(&args[1])?;
+    if buckets < 1.0 {
+        bail!(Type, "`ntile` number of buckets must be >= 1");
+    }
+
+    let buckets = buckets as usize;
+    let function = move |s: Series| {
+        let ranks = s.idx()?;
+        let count = ranks.len() - ranks.null_count();
+        let size = count / buckets;
+        let large = (count % buckets) * (size + 1);
+
+        let values = ranks
+            .into_iter()
+            .map(|rank| {
+                rank.map(|rank| {
+                    let idx = rank as usize - 1;
+                    let bucket = if idx < large {
+                        idx / (size + 1)
+                    } else {
+                        (idx - large) / size + count % buckets
+                    };
+                    bucket as u32 + 1
+                })
+            })
+            .collect::<UInt32Chunked>();
+
+        Ok(Some(values.with_name(s.name()).into_series()))
+    };
+
+    eval_rank(&args[0], RankMethod::Ordinal, schema)
+        .map(|e| e.apply(function, GetOutput::from_type(DataType::UInt32)))
+}
+
+/// Evaluates the rank of an expression values.
+fn eval_rank(expr: &Expr, method: RankMethod, schema: &Schema) -> Result<PolarsExpr> {
+    let options = RankOptions {
+        method,
+        descending: false,
+    };
+
+    eval(expr, schema).map(|e| e.rank(options, None))
+}
+
+/// Evaluates `case_when(cond1 ~ value1, cond2 ~ value2, true ~ default)`.
+///
+/// Rows that don't match any condition are set to null when there is no
+/// `true` default branch.
+fn eval_case_when(args: &[Expr], schema: &Schema) -> Result<PolarsExpr> {
+    let mut branches = Vec::with_capacity(args.len());
+    let mut otherwise = lit(NULL);
+
+    for (idx, arg) in args.iter().enumerate() {
+        let Expr::BinaryOp(lhs, Operator::Formula, rhs) = arg else {
+            bail!(Type, "Unexpected case_when expression: {arg}");
+        };
+
+        let value = eval(rhs, schema)?;
+        if matches!(lhs.as_ref(), Expr::Identifier(s) if s == "true") {
+            if idx != args.len() - 1 {
+                bail!(Type, "case_when `true` branch must be the last one");
+            }
+
+            otherwise = value;
+        } else if matches!(lhs.as_ref(), Expr::Identifier(s) if s == "false") {
+            branches.push((lit(false), value));
+        } else {
+            branches.push((eval(lhs, schema)?, value));
+        }
+    }
+
+    let mut branches = branches.into_iter();
+    let Some((condition, value)) = branches.next() else {
+        return Ok(otherwise);
+    };
+
+    let then = when(condition).then(value);
+    let Some((condition, value)) = branches.next() else {
+        return Ok(then.otherwise(otherwise));
+    };
+
+    let mut then = then.when(condition).then(value);
+    for (condition, value) in branches {
+        then = then.when(condition).then(value);
+    }
+
+    Ok(then.otherwise(otherwise))
+}
+
+/// Evaluates `str_pad(name, 10, side = "right", pad = "0")`.
+fn eval_str_pad(args: &[Expr], schema: &Schema) -> Result<PolarsExpr> {
+    let expr = eval_string("str_pad", &args[0], schema)?;
+
+    let mut width = None;
+    let mut side = "left".to_string();
+    let mut pad = ' ';
+
+    for arg in &args[1..] {
+        match arg {
+            arg if arg.number().is_some_and(|n| n >= 0.0) => {
+                width = arg.number().map(|n| n as usize)
+            }
+            Expr::BinaryOp(lhs, Operator::Assign, rhs) => match args::identifier(lhs)?.as_str() {
+                "side" => side = args::string(rhs)?,
+                "pad" => {
+                    let value = args::string(rhs)?;
+                    let mut chars = value.chars();
+                    match (chars.next(), chars.next()) {
+                        (Some(c), None) => pad = c,
+                        _ => bail!(Type, "`str_pad` pad must be a single character"),
+                    }
+                }
+                name => bail!(Type, "Unknown `str_pad` argument '{name}'"),
+            },
+            _ => bail!(Type, "Invalid `str_pad` argument '{arg}'"),
+        }
+    }
+
+    let Some(width) = width else {
+        bail!(Type, "`str_pad` missing width argument");
+    };
+
+    match side.as_str() {
+        "left" => Ok(expr.str().pad_start(width, pad)),
+        "right" => Ok(expr.str().pad_end(width, pad)),
+        _ => Err(err!(Type, "`str_pad` side must be \"left\" or \"right\"")),
+    }
+}
+
+/// Evaluates a function argument that must be a string expression.
+fn eval_string(fname: &str, expr: &Expr, schema: &Schema) -> Result<PolarsExpr> {
+    let result = eval(expr, schema)?;
+    let dtype = types::dtype(&result, schema)?;
+    types::check_arg(fname, &expr.to_string(), &dtype, "a string", |dt| {
+        dt.is_string()
+    })?;
+    Ok(result)
+}
+
+/// Evaluates a function argument that must be a date or a datetime expression.
+fn eval_temporal(fname: &str, expr: &Expr, schema: &Schema) -> Result<PolarsExpr> {
+    let result = eval(expr, schema)?;
+    let dtype = types::dtype(&result, schema)?;
+    types::check_arg(
+        fname,
+        &expr.to_string(),
+        &dtype,
+        "a date or datetime",
+        |dt| matches!(dt, DataType::Date | DataType::Datetime(_, _)),
+    )?;
+    Ok(result)
+}
+
+/// Evaluates a function that extracts a part of a date like `year(ts)`.
+fn eval_date_part(fname: &str, expr: &Expr, schema: &Schema) -> Result<PolarsExpr> {
+    let dt = eval_temporal(fname, expr, schema)?.dt();
+
+    let part = match fname {
+        "year" => dt.year(),
+        "quarter" => dt.quarter(),
+        "month" => dt.month(),
+        "day" => dt.day(),
+        "hour" => dt.hour(),
+        "minute" => dt.minute(),
+        "second" => dt.second(),
+        "weekday" => dt.weekday(),
+        "week" => dt.week(),
+        _ => dt.ordinal_day(),
+    };
+
+    Ok(part)
+}
+
+/// Returns a date unit like `"15m"` or `"1mo"` checking that it is valid.
+fn date_unit(fname: &str, expr: &Expr) -> Result<String> {
+    let unit = args::string(expr)?;
+    let re = regex::Regex::new(r"^(\d+(ns|us|ms|s|mo|m|h|d|w|q|y))+$").unwrap();
+    if !re.is_match(&unit) {
+        bail!(
+            Type,
+            "`{fname}` invalid unit '{unit}', expected a duration like 15m, 1h, 1d, or 1mo"
+        );
+    }
+
+    Ok(unit)
+}
+
+/// Evaluates a function argument that must be a numeric expression.
+fn eval_number(fname: &str, expr: &Expr, schema: &Schema) -> Result<PolarsExpr> {
+    let result = eval(expr, schema)?;
+    let dtype = types::dtype(&result, schema)?;
+    types::check_arg(fname, &expr.to_string(), &dtype, "numeric", |dt| {
+        dt.is_numeric()
+    })?;
+    Ok(result)
+}
+
+/// Returns a literal for a regex argument checking that the regex is valid.
+fn regex(fname: &str, expr: &Expr) -> Result<PolarsExpr> {
+    let pattern = args::string(expr)?;
+    polars::export::regex::Regex::new(&pattern)
+        .map_err(|_| err!(Type, "`{fname}` invalid regex '{pattern}'"))?;
+    Ok(lit(pattern))
+}
+
+/// Checks the type of a function column argument.
+fn check_column(
+    fname: &str,
+    expr: &Expr,
+    schema: &Schema,
+    expected: &str,
+    is_valid: impl Fn(&DataType) -> bool,
+) -> Result<()> {
+    let column = args::identifier(expr)?;
+    let dtype = types::column(&column, schema)?;
+    types::check_arg(fname, &column, &dtype, expected, is_valid)
+}
+
+/// Checks the type of an aggregation column argument.
+fn check_agg(fname: &str, expr: &Expr, schema: &Schema) -> Result<()> {
+    let column = args::identifier(expr)?;
+    let dtype = types::column(&column, schema)?;
+    types::check_agg(fname, &column, &dtype)
+}
+
+/// Evaluates `contains(x, pattern)` on a string or a list column, the pattern
+/// is a regex unless `literal = true`.
+fn eval_contains(args: &[Expr], schema: &Schema) -> Result<PolarsExpr> {
+    let column = args::identifier(&args[0])?;
+    let column_type = schema.get(&column).ok_or_else(|| {
+        let hint = fuzzy::did_you_mean(&column, schema.iter_names().map(|c| c.as_str()));
+        err!(Schema, "Unknown contains column '{column}'{hint}")
+    })?;
+
+    let (pattern, literal) = pattern_arg("contains", &args[1..], "literal")?;
+    match column_type {
+        DataType::List(elem_type) => {
+            // Negative numbers are matched as number literals.
+            let number = pattern.number().map(Expr::Number);
+            let pattern = number.as_ref().unwrap_or(pattern);
+            list_contains(&column, pattern, elem_type, literal)
+        }
+        DataType::String => string_contains(&column, pattern, literal),
+        _ => Err(err!(Type, "Column '{column}' must be a str or a list")),
+    }
+}
+
+/// Returns the pattern of a string predicate and the value of its named flag.
+fn pattern_arg<'a>(fname: &str, args: &'a [Expr], flag: &str) -> Result<(&'a Expr, bool)> {
+    let mut patterns = args
+        .iter()
+        .filter(|arg| !matches!(arg, Expr::BinaryOp(_, Operator::Assign, _)));
+
+    match (patterns.next(), patterns.next()) {
+        (Some(pattern), None) => Ok((pattern, args::named_bool(args, flag)?)),
+        _ => bail!(Type, "`{fname}` expects one pattern argument"),
+    }
+}
+
+/// Evaluates `between(x, lo, hi)`, bounds are inclusive.
+fn eval_between(args: &[Expr], schema: &Schema) -> Result<PolarsExpr> {
+    let value = eval(&args[0], schema)?;
+    let value_type = types::dtype(&value, schema)?;
+
+    let mut bounds = Vec::with_capacity(2);
+    for bound in &args[1..] {
+        let bound_expr = eval(bound, schema)?;
+        types::check_compare(
+            (&args[0], &value_type),
+            (bound, &types::dtype(&bound_expr, schema)?),
+        )?;
+        bounds.push(bound_expr);
+    }
+
+    let hi = bounds.pop().unwrap();
+    let lo = bounds.pop().unwrap();
+    Ok(value.clone().gt_eq(lo).and(value.lt_eq(hi)))
+}
+
+/// Evaluates a membership test on a list of values or on a column of another
+/// dataframe.
+fn is_in(column: &Expr, values: &Expr, schema: &Schema) -> Result<PolarsExpr> {
+    let column_expr = args::column(column, schema)?;
+    let column_type = types::dtype(&column_expr, schema)?;
+
+    let values = match values {
+        Expr::List(values) => values.as_slice(),
+        Expr::Identifier(values) => {
+            let flag = filter::member_flag(&args::identifier(column)?, values);
+            if !schema.contains(&flag) {
+                bail!(
+                    Type,
+                    "is_in with a dataframe column '{values}' is only supported by filter"
+                );
+            }
+
+            return Ok(col(&flag).is_not_null());
+        }
+        value => std::slice::from_ref(value),
+    };
+
+    let mut numbers = Vec::with_capacity(values.len());
+    let mut strings = Vec::with_capacity(values.len());
+    for value in values {
+        let value_type = match value {
+            value if value.number().is_some() => {
+                numbers.extend(value.number());
+                DataType::Float64
+            }
+            Expr::String(s) => {
+                strings.push(s.as_str());
+                DataType::String
+            }
+            _ => bail!(Type, "Unexpected is_in value {value}"),
+        };
+
+        types::check_compare((column, &column_type), (value, &value_type))?;
+    }
+
+    if values.is_empty() {
+        Ok(lit(false))
+    } else if !numbers.is_empty() {
+        let values = Series::new("", numbers);
+        Ok(column_expr.cast(DataType::Float64).is_in(lit(values)))
+    } else {
+        let values = Series::new("", strings);
+        Ok(column_expr.cast(DataType::String).is_in(lit(values)))
+    }
+}
+
+/// Negates the evaluated expression `value` of `expr`.
+pub fn negate(expr: &Expr, value: PolarsExpr, schema: &Schema) -> Result<PolarsExpr> {
+    let dtype = types::dtype(&value, schema)?;
+    types::check_negate(expr, &dtype)?;
+
+    // Unsigned and boolean values cannot be negated in place.
+    if dtype.is_unsigned_integer() || dtype.is_bool() {
+        Ok(-value.cast(DataType::Int64))
+    } else {
+        Ok(-value)
+    }
+}
+
+fn list_contains(
+    column: &str,
+    pattern: &Expr,
+    elem_type: &DataType,
+    literal: bool,
+) -> Result<PolarsExpr> {
+    use DataType::*;
+
+    match (elem_type, pattern) {
+        (Int8, Expr::Number(n)) => Ok(col(column).list().contains(lit(*n as i8))),
+        (Int16, Expr::Number(n)) => Ok(col(column).list().contains(lit(*n as i16))),
+        (Int32, Expr::Number(n)) => Ok(col(column).list().contains(lit(*n as i32))),
+        (Int64, Expr::Number(n)) => Ok(col(column).list().contains(lit(*n as i64))),
+        (UInt8, Expr::Number(n)) => Ok(col(column).list().contains(lit(*n as u8))),
+        (UInt16, Expr::Number(n)) => Ok(col(column).list().contains(lit(*n as u16))),
+        (UInt32, Expr::Number(n)) => Ok(col(column).list().contains(lit(*n as u32))),
+        (UInt64, Expr::Number(n)) => Ok(col(column).list().contains(lit(*n as u64))),
+        (Float32, Expr::Number(n)) => Ok(col(column).list().contains(lit(*n as f32))),
+        (Float64, Expr::Number(n)) => Ok(col(column).list().contains(lit(*n))),
+        (String, Expr::String(s)) if literal => Ok(col(column).list().contains(lit(s.to_owned()))),
+        (String, Expr::String(s)) => {
+            let re = regex::Regex::new(s)
+                .map_err(|_| err!(Type, "invalid contains regex '{s}' for column '{column}'"))?;
+
+            let function = move |s: Series| {
+                let ca = s.list()?;
+                let mut bools = Vec::with_capacity(ca.len());
+
+                ca.into_iter().for_each(|arr| {
+                    let found = if let Some(s) = arr {
+                        s.str()
+                            .map(|ca| {
+                                ca.into_iter()
+                                    .any(|s| s.map(|s| re.is_match(s)).unwrap_or(false))
+                            })
+                            .unwrap_or_default()
+                    } else {
+                        false
+                    };
+
+                    bools.push(found);
+                });
+
+                Ok(Some(BooleanChunked::new(ca.name(), bools).into_series()))
+            };
+
+            // Using apply avoid crash with debug build.
+            Ok(col(column).apply(function, GetOutput::from_type(DataType::Boolean)))
+        }
+        _ => bail!(
+            Type,
+            "contains error: invalid type {elem_type} for column '{column}'"
+        ),
+    }
+}
+
+fn string_contains(column: &str, pattern: &Expr, literal: bool) -> Result<PolarsExpr> {
+    if let (Expr::String(s), true) = (pattern, literal) {
+        Ok(col(column).str().contains_literal(lit(s.to_owned())))
+    } else if let Expr::String(re) = pattern {
+        regex::Regex::new(re)
+            .map_err(|_| err!(Type, "invalid contains regex '{re}' for column '{column}'"))?;
+
+        Ok(col(column).str().contains(lit(re.to_owned()), false))
+    } else {
+        Err(err!(
+            Type,
+            "contains predicate for column '{column}' must be a regex"
+        ))
+    }
+}
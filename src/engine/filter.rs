@@ -12,11 +12,10 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
-use anyhow::{bail, Result};
-use polars::export::regex;
 use polars::lazy::dsl::Expr as PolarsExpr;
 use polars::prelude::*;
 
+use crate::error::{bail, Result};
 use crate::parser::{Expr, Operator};
 
 use super::*;
@@ -25,139 +24,147 @@ use super::*;
 ///
 /// Parameters are checked before evaluation by the typing module.
 pub fn eval(args: &[Expr], ctx: &mut Context) -> Result<()> {
-    if let Some(mut df) = ctx.take_df() {
-        for arg in args {
-            let expr = df
-                .schema()
-                .map_err(anyhow::Error::from)
-                .and_then(|schema| eval_expr(arg, &schema))
-                .map_err(|e| anyhow!("filter error: {e}"))?;
-            df = df.filter(expr);
-        }
-
+    if let Some(df) = ctx.take_df() {
+        let df = eval_args(args, df, &[], ctx)?;
         ctx.set_df(df)?;
-    } else if ctx.is_grouping() {
-        bail!("filter error: must call summarize after a group_by");
+    } else if let Some((df, keys)) = ctx.take_group_df() {
+        // Grouped conditions are evaluated over each group.
+        let partition = keys.iter().map(|k| col(k)).collect::<Vec<_>>();
+        let df = eval_args(args, df, &partition, ctx)?;
+        ctx.set_group(df.group_by_stable(&partition), keys)?;
     } else {
-        bail!("filter error: missing input dataframe");
+        bail!(Type, "filter error: missing input dataframe");
     }
 
     Ok(())
 }
 
-fn eval_expr(expr: &Expr, schema: &Schema) -> Result<PolarsExpr> {
-    match expr {
-        Expr::BinaryOp(lhs, op, rhs) => {
-            let lhs = eval_expr(lhs, schema)?;
-            let rhs = eval_expr(rhs, schema)?;
-
-            let result = match op {
-                Operator::Eq => lhs.eq(rhs),
-                Operator::NotEq => lhs.neq(rhs),
-                Operator::Lt => lhs.lt(rhs),
-                Operator::LtEq => lhs.lt_eq(rhs),
-                Operator::Gt => lhs.gt(rhs),
-                Operator::GtEq => lhs.gt_eq(rhs),
-                Operator::And => lhs.and(rhs),
-                Operator::Or => lhs.or(rhs),
-                _ => panic!("Unexpected filter operator {op}"),
-            };
-
-            Ok(result)
+fn eval_args(
+    args: &[Expr],
+    mut df: LazyFrame,
+    partition: &[PolarsExpr],
+    ctx: &Context,
+) -> Result<LazyFrame> {
+    let mut members = Vec::new();
+    args.iter()
+        .for_each(|arg| dataframe_members(arg, &mut members));
+
+    let mut flags = Vec::with_capacity(members.len());
+    for (column, values) in members {
+        let flag = member_flag(column, values);
+        if !flags.contains(&flag) {
+            df = join_member(df, column, values, &flag, ctx)
+                .map_err(|e| e.context("filter error"))?;
+            flags.push(flag);
         }
-        Expr::Identifier(_) => args::column(expr, schema),
-        Expr::String(s) => Ok(lit(s.clone())),
-        Expr::Number(n) => Ok(lit(*n)),
-        Expr::Function(name, args) if name == "dt" => {
-            let ts = args::timestamp(&args[0])?;
-            Ok(lit(ts))
+    }
+
+    for arg in args {
+        let mut expr = df
+            .schema()
+            .map_err(Error::from_schema)
+            .and_then(|schema| expression::eval(arg, &schema))
+            .map_err(|e| e.context("filter error"))?;
+
+        if !partition.is_empty() {
+            expr = expr.over(partition);
         }
-        Expr::UnaryOp(Operator::Not, expr) => eval_predicate(expr, schema).map(|expr| expr.not()),
-        Expr::Function(_, _) => eval_predicate(expr, schema),
-        _ => panic!("Unexpected filter expression {expr}"),
+
+        df = df.filter(expr);
+    }
+
+    if !flags.is_empty() {
+        df = df.select([all().exclude(flags)]);
     }
+
+    Ok(df)
 }
 
-fn eval_predicate(expr: &Expr, schema: &Schema) -> Result<PolarsExpr> {
+/// Collects the membership tests on a column of another dataframe like
+/// `is_in(x, df.col)` or `x %in% df.col`.
+fn dataframe_members<'a>(expr: &'a Expr, members: &mut Vec<(&'a str, &'a str)>) {
     match expr {
-        Expr::Function(name, args) if name == "contains" => {
-            let column = args::identifier(&args[0]);
-            let column_type = schema
-                .get(&column)
-                .ok_or_else(|| anyhow!("Unknown contains column '{column}'"))?;
-
-            match column_type {
-                DataType::List(elem_type) => list_contains(&column, &args[1], elem_type),
-                DataType::String => string_contains(&column, &args[1]),
-                _ => Err(anyhow!("Column '{column}' must be a str or a list")),
+        Expr::BinaryOp(lhs, Operator::In, rhs) => {
+            if let (Expr::Identifier(column), Expr::Identifier(values)) = (&**lhs, &**rhs) {
+                if values.contains('.') {
+                    members.push((column, values));
+                }
             }
         }
-        Expr::Function(name, args) if name == "is_null" => {
-            let r = args::column(&args[0], schema).map(|c| c.is_null());
-            println!("{r:?}");
-            r
+        Expr::Function(name, args) if name == "is_in" && args.len() == 2 => {
+            if let (Expr::Identifier(column), Expr::Identifier(values)) = (&args[0], &args[1]) {
+                if values.contains('.') {
+                    members.push((column, values));
+                }
+            }
         }
-        _ => panic!("Unexpected filter expression {expr}"),
-    }
-}
-
-fn list_contains(column: &str, pattern: &Expr, elem_type: &DataType) -> Result<PolarsExpr> {
-    println!("contain {elem_type}");
-    use DataType::*;
-
-    match (elem_type, pattern) {
-        (Int8, Expr::Number(n)) => Ok(col(column).list().contains(lit(*n as i8))),
-        (Int16, Expr::Number(n)) => Ok(col(column).list().contains(lit(*n as i16))),
-        (Int32, Expr::Number(n)) => Ok(col(column).list().contains(lit(*n as i32))),
-        (Int64, Expr::Number(n)) => Ok(col(column).list().contains(lit(*n as i64))),
-        (UInt8, Expr::Number(n)) => Ok(col(column).list().contains(lit(*n as u8))),
-        (UInt16, Expr::Number(n)) => Ok(col(column).list().contains(lit(*n as u16))),
-        (UInt32, Expr::Number(n)) => Ok(col(column).list().contains(lit(*n as u32))),
-        (UInt64, Expr::Number(n)) => Ok(col(column).list().contains(lit(*n as u64))),
-        (Float32, Expr::Number(n)) => Ok(col(column).list().contains(lit(*n as f32))),
-        (Float64, Expr::Number(n)) => Ok(col(column).list().contains(lit(*n))),
-        (String, Expr::String(s)) => {
-            let re = regex::Regex::new(s)
-                .map_err(|_| anyhow!("invalid contains regex '{s}' for column '{column}'"))?;
-
-            let function = move |s: Series| {
-                let ca = s.list()?;
-                let mut bools = Vec::with_capacity(ca.len());
-
-                ca.into_iter().for_each(|arr| {
-                    let found = if let Some(s) = arr {
-                        s.str()
-                            .map(|ca| {
-                                ca.into_iter()
-                                    .any(|s| s.map(|s| re.is_match(s)).unwrap_or(false))
-                            })
-                            .unwrap_or_default()
-                    } else {
-                        false
-                    };
-
-                    bools.push(found);
-                });
-
-                Ok(Some(BooleanChunked::new(ca.name(), bools).into_series()))
-            };
-
-            // Using apply avoid crash with debug build.
-            Ok(col(column).apply(function, GetOutput::from_type(DataType::Boolean)))
+        Expr::BinaryOp(lhs, _, rhs) => {
+            dataframe_members(lhs, members);
+            dataframe_members(rhs, members);
         }
-        _ => bail!("contains error: invalid type {elem_type} for column '{column}'"),
+        Expr::UnaryOp(_, expr) => dataframe_members(expr, members),
+        _ => {}
     }
 }
 
-fn string_contains(column: &str, pattern: &Expr) -> Result<PolarsExpr> {
-    if let Expr::String(re) = pattern {
-        regex::Regex::new(re)
-            .map_err(|_| anyhow!("invalid contains regex '{re}' for column '{column}'"))?;
+/// Returns the name of the column that flags the rows with a member value.
+pub fn member_flag(column: &str, values: &str) -> String {
+    format!("__dply_is_in_{column}_{values}")
+}
 
-        Ok(col(column).str().contains(lit(re.to_owned()), false))
+/// Adds a column that is not null for the rows where `column` has one of the
+/// values of the other dataframe column, the rows are matched with a left join
+/// on the unique values.
+fn join_member(
+    df: LazyFrame,
+    column: &str,
+    values: &str,
+    flag: &str,
+    ctx: &Context,
+) -> Result<LazyFrame> {
+    const KEY: &str = "__dply_is_in_key";
+
+    let (name, values_column) = values.split_once('.').unwrap_or_default();
+    let Some(values_df) = ctx.get_df(name) else {
+        let hint = fuzzy::did_you_mean(name, ctx.vars.keys().map(|v| v.as_str()));
+        bail!(Schema, "undefined dataframe variable '{name}'{hint}");
+    };
+
+    let ltype = types::column(column, df.schema().map_err(Error::from_schema)?.as_ref())?;
+    let rtype = types::column(
+        values_column,
+        values_df.schema().map_err(Error::from_schema)?.as_ref(),
+    )?;
+    types::check_compare(
+        (&Expr::Identifier(column.to_string()), &ltype),
+        (&Expr::Identifier(values.to_string()), &rtype),
+    )?;
+
+    let dtype = if ltype == rtype {
+        ltype
+    } else if ltype.is_numeric() && rtype.is_numeric() {
+        DataType::Float64
     } else {
-        Err(anyhow!(
-            "contains predicate for column '{column}' must be a regex"
-        ))
-    }
+        DataType::String
+    };
+
+    let values_df = values_df
+        .clone()
+        .select([
+            col(values_column).cast(dtype.clone()).alias(KEY),
+            lit(true).alias(flag),
+        ])
+        .unique(None, UniqueKeepStrategy::Any);
+
+    let df = df
+        .with_column(col(column).cast(dtype).alias(KEY))
+        .join(
+            values_df,
+            [col(KEY)],
+            [col(KEY)],
+            JoinArgs::new(JoinType::Left),
+        )
+        .select([all().exclude([KEY])]);
+
+    Ok(df)
 }
@@ -1,12 +1,13 @@
 // Copyright (C) 2023 Vince Vasta
 // SPDX-License-Identifier: Apache-2.0
-use anyhow::Result;
 use comfy_table::presets;
 use comfy_table::{ColumnConstraint, ContentArrangement, Width};
 use comfy_table::{Row, Table};
 use polars::prelude::*;
 use std::{env, io::Write};
 
+use crate::error::{err, Result};
+
 /// Prints a dataframe in test format, used for test comparisons.
 pub fn df_test(out: &mut dyn Write, df: DataFrame) -> Result<()> {
     env::set_var("POLARS_FMT_TABLE_CELL_LIST_LEN", "6");
@@ -80,7 +81,10 @@ pub fn glimpse(w: &mut dyn Write, df: LazyFrame) -> Result<()> {
     table.set_header(vec![info, "Type".into(), "Values".into()]);
 
     if let Ok(slen) = std::env::var("POLARS_FMT_STR_LEN") {
-        table.set_width(slen.parse()?);
+        table.set_width(
+            slen.parse()
+                .map_err(|e| err!(Engine, "Invalid POLARS_FMT_STR_LEN value: {e}"))?,
+        );
     }
 
     for col in df.get_columns() {
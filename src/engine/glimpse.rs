@@ -1,7 +1,6 @@
 // Copyright (C) 2023 Vince Vasta
 // SPDX-License-Identifier: Apache-2.0
-use anyhow::{bail, Result};
-
+use crate::error::{bail, Result};
 use crate::parser::Expr;
 
 use super::*;
@@ -10,12 +9,10 @@ use super::*;
 ///
 /// Parameters are checked before evaluation by the typing module.
 pub fn eval(_args: &[Expr], ctx: &mut Context) -> Result<()> {
-    if let Some(df) = ctx.take_df() {
+    if let Some((df, _)) = ctx.take_df_or_group() {
         ctx.glimpse(df)?;
-    } else if ctx.is_grouping() {
-        bail!("glimpse error: must call summarize after a group_by");
     } else {
-        bail!("glimpse error: missing input dataframe");
+        bail!(Type, "glimpse error: missing input dataframe");
     }
 
     Ok(())
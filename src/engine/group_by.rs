@@ -1,8 +1,8 @@
 // Copyright (C) 2023 Vince Vasta
 // SPDX-License-Identifier: Apache-2.0
-use anyhow::{bail, Result};
 use polars::prelude::*;
 
+use crate::error::{bail, Result};
 use crate::parser::Expr;
 
 use super::*;
@@ -14,23 +14,26 @@ pub fn eval(args: &[Expr], ctx: &mut Context) -> Result<()> {
     if let Some(df) = ctx.take_df() {
         let schema_cols = ctx.columns();
         let mut columns = Vec::new();
+        let mut keys = Vec::new();
 
         for arg in args {
             if let Expr::Identifier(column) = arg {
                 if !schema_cols.contains(column) {
-                    bail!("group_by error: Unknown column {column}");
+                    let hint = fuzzy::did_you_mean(column, schema_cols.iter().map(|c| c.as_str()));
+                    bail!(Schema, "group_by error: Unknown column '{column}'{hint}");
                 }
 
                 let expr = col(column);
                 if !columns.contains(&expr) {
                     columns.push(expr);
+                    keys.push(column.to_owned());
                 }
             }
         }
 
-        ctx.set_group(df.group_by_stable(&columns))?;
+        ctx.set_group(df.group_by_stable(&columns), keys)?;
     } else {
-        bail!("group_by error: missing input dataframe");
+        bail!(Type, "group_by error: missing input dataframe");
     }
 
     Ok(())
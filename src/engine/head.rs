@@ -1,7 +1,6 @@
 // Copyright (C) 2023 Vince Vasta
 // SPDX-License-Identifier: Apache-2.0
-use anyhow::{bail, Result};
-
+use crate::error::{bail, Result};
 use crate::parser::Expr;
 
 use super::*;
@@ -10,19 +9,16 @@ use super::*;
 ///
 /// Parameters are checked before evaluation by the typing module.
 pub fn eval(args: &[Expr], ctx: &mut Context) -> Result<()> {
-    if let Some(df) = ctx.take_df() {
+    if let Some((df, _)) = ctx.take_df_or_group() {
         let limit = if !args.is_empty() {
-            args::number(&args[0]) as u32
+            args::number(&args[0])? as u32
         } else {
             10
         };
 
-        let df = df.limit(limit).collect()?;
-        ctx.print(df)?;
-    } else if ctx.is_grouping() {
-        bail!("head error: must call summarize after a group_by");
+        ctx.print(df.limit(limit))?;
     } else {
-        bail!("head error: missing input dataframe");
+        bail!(Type, "head error: missing input dataframe");
     }
 
     Ok(())
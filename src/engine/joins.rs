@@ -1,9 +1,10 @@
 // Copyright (C) 2023 Vince Vasta
 // SPDX-License-Identifier: Apache-2.0
-use anyhow::{bail, Result};
+use polars::lazy::dsl::Expr as PolarsExpr;
 use polars::prelude::*;
 use std::collections::HashSet;
 
+use crate::error::{bail, err, Result};
 use crate::parser::{Expr, Operator};
 
 use super::*;
@@ -13,76 +14,766 @@ use super::*;
 /// Parameters are checked before evaluation by the typing module.
 pub fn eval(args: &[Expr], ctx: &mut Context, join_type: JoinType) -> Result<()> {
     if let Some(lhs_df) = ctx.take_df() {
-        let rhs_df_name = args::identifier(&args[0]);
+        let rhs_df_name = args::identifier(&args[0])?;
         if let Some(rhs_df) = ctx.get_df(&rhs_df_name) {
-            let lhs_schema = lhs_df.schema().map_err(|e| anyhow!("join error: {e}"))?;
-            let rhs_schema = rhs_df.schema().map_err(|e| anyhow!("join error: {e}"))?;
-
-            let lhs_schema_cols = lhs_schema
-                .iter_names()
-                .map(|s| s.to_string())
-                .collect::<HashSet<_>>();
-
-            let rhs_schema_cols = rhs_schema
-                .iter_names()
-                .map(|s| s.to_string())
-                .collect::<HashSet<_>>();
-
-            // If no join columns are specified use common columns
-            let (lhs_cols, rhs_cols) = if args.len() == 1 {
-                let common_cols = lhs_schema_cols
-                    .intersection(&rhs_schema_cols)
-                    .map(|s| col(s))
+            let mut join_args =
+                JoinArgs::new(join_type).with_coalesce(JoinCoalesce::CoalesceColumns);
+            let mut validation = JoinValidation::ManyToMany;
+            let mut conditions = Vec::with_capacity(args.len());
+
+            for arg in args.iter().skip(1) {
+                match arg {
+                    Expr::BinaryOp(lhs, Operator::Assign, rhs) => {
+                        match args::identifier(lhs)?.as_str() {
+                            "suffix" => join_args.suffix = Some(args::string(rhs)?),
+                            "validate" => validation = join_validation(rhs)?,
+                            _ => {}
+                        }
+                    }
+                    _ => conditions.push(arg),
+                }
+            }
+
+            let lhs_schema = lhs_df
+                .schema()
+                .map_err(|e| Error::from_schema(e).context("join error"))?;
+            let rhs_schema = rhs_df
+                .schema()
+                .map_err(|e| Error::from_schema(e).context("join error"))?;
+
+            let mut lhs_cols = Vec::with_capacity(args.len());
+            let mut rhs_cols = Vec::with_capacity(args.len());
+            let mut ranges = Vec::new();
+            let mut equal_keys = Vec::new();
+
+            if conditions.is_empty() {
+                // If no join columns are specified use common columns
+                let rhs_schema_cols = rhs_schema
+                    .iter_names()
+                    .map(|s| s.to_string())
+                    .collect::<HashSet<_>>();
+
+                lhs_cols = lhs_schema
+                    .iter_names()
+                    .filter(|c| rhs_schema_cols.contains(c.as_str()))
+                    .map(|c| col(c))
                     .collect::<Vec<_>>();
-                if common_cols.is_empty() {
-                    bail!("join error: Missing join columns for '{rhs_df_name}'");
+                if lhs_cols.is_empty() {
+                    bail!(
+                        Schema,
+                        "join error: Missing join columns for '{rhs_df_name}'"
+                    );
                 }
 
-                (common_cols.clone(), common_cols)
-            } else {
-                let mut lhs_cols = Vec::with_capacity(args.len());
-                let mut rhs_cols = Vec::with_capacity(args.len());
-
-                for arg in args.iter().skip(1) {
-                    if let Expr::BinaryOp(lhs, Operator::Eq, rhs) = arg {
-                        let lhs_col = args::identifier(lhs);
-                        if !lhs_schema_cols.contains(&lhs_col) {
-                            bail!("join error: Unknown column '{lhs_col}'");
-                        }
-                        lhs_cols.push(col(&lhs_col));
+                rhs_cols.clone_from(&lhs_cols);
+            }
 
-                        let rhs_col = args::identifier(rhs);
-                        if !rhs_schema_cols.contains(&rhs_col) {
-                            bail!("join error: Unknown column '{rhs_col}'");
-                        }
-                        rhs_cols.push(col(&rhs_col));
+            for arg in conditions {
+                let Expr::BinaryOp(lhs, op, rhs) = arg else {
+                    continue;
+                };
+
+                let lhs_col = args::identifier(lhs)?;
+                check_column("join", &lhs_col, &lhs_schema)?;
+
+                let rhs_col = args::identifier(rhs)?;
+                check_column("join", &rhs_col, &rhs_schema)?;
+
+                let lhs_type = lhs_schema.try_get(&lhs_col)?;
+                let rhs_type = rhs_schema.try_get(&rhs_col)?;
 
-                        let have_same_type = lhs_schema
-                            .get(&lhs_col)
-                            .zip(rhs_schema.get(&rhs_col))
-                            .map(|(ldt, rdt)| ldt == rdt)
-                            .unwrap_or(false);
-                        if !have_same_type {
+                match op {
+                    Operator::Eq => {
+                        if lhs_type != rhs_type {
                             bail!(
+                                Type,
                                 "join error: '{lhs_col}' and '{rhs_col}' don't have the same type"
                             );
                         }
+
+                        lhs_cols.push(col(&lhs_col));
+                        rhs_cols.push(col(&rhs_col));
+                        equal_keys.push((lhs_col, rhs_col));
+                    }
+                    Operator::Lt | Operator::LtEq | Operator::Gt | Operator::GtEq => {
+                        let comparable = (lhs_type.is_numeric() && rhs_type.is_numeric())
+                            || (lhs_type == rhs_type && lhs_type.is_temporal());
+                        if !comparable {
+                            bail!(
+                                Type,
+                                "join error: cannot compare '{lhs_col}' ({lhs_type}) with '{rhs_col}' ({rhs_type})"
+                            );
+                        }
+
+                        ranges.push(Range {
+                            lhs: lhs_col,
+                            op: *op,
+                            rhs: rhs_col,
+                        });
                     }
+                    _ => bail!(Type, "join error: unsupported join condition '{arg}'"),
                 }
+            }
 
-                (lhs_cols, rhs_cols)
-            };
+            if !ranges.is_empty() {
+                let lhs_df = lhs_df.with_row_index(LHS_ROW, None);
+                let rhs_df = rhs_df.clone().with_row_index(RHS_ROW, None);
+                let pairs = range_pairs(
+                    &lhs_df,
+                    &rhs_df,
+                    (lhs_cols, rhs_cols),
+                    &ranges,
+                    ctx.is_dry_run(),
+                )?;
+
+                if !ctx.is_dry_run() {
+                    // Each match is a pair of rows, a row that is matched more than
+                    // once is like a duplicate key.
+                    if let JoinValidation::OneToOne | JoinValidation::OneToMany = validation {
+                        check_unique_keys(&pairs, &[col(RHS_ROW)], "the input", &validation)?;
+                    }
+
+                    if let JoinValidation::OneToOne | JoinValidation::ManyToOne = validation {
+                        check_unique_keys(&pairs, &[col(LHS_ROW)], &rhs_df_name, &validation)?;
+                    }
+                }
+
+                let df = join_pairs(lhs_df, rhs_df, pairs, &equal_keys, &join_args)?;
+                return ctx.set_df(df);
+            }
+
+            if validation.is_valid_join(&join_args.how).is_err() {
+                bail!(
+                    Type,
+                    "join error: {validation} validation is only supported by inner, left, and outer joins"
+                );
+            }
+
+            if !ctx.is_dry_run() {
+                if let JoinValidation::OneToOne | JoinValidation::OneToMany = validation {
+                    check_unique_keys(&lhs_df, &lhs_cols, "the input", &validation)?;
+                }
+
+                if let JoinValidation::OneToOne | JoinValidation::ManyToOne = validation {
+                    check_unique_keys(rhs_df, &rhs_cols, &rhs_df_name, &validation)?;
+                }
+            }
 
-            let join_args = JoinArgs::new(join_type).with_coalesce(JoinCoalesce::CoalesceColumns);
             ctx.set_df(lhs_df.join(rhs_df.clone(), lhs_cols, rhs_cols, join_args))?;
         } else {
-            bail!("join error: undefined dataframe variable '{rhs_df_name}'");
+            let hint = fuzzy::did_you_mean(&rhs_df_name, ctx.vars.keys().map(|v| v.as_str()));
+            bail!(
+                Schema,
+                "join error: undefined dataframe variable '{rhs_df_name}'{hint}"
+            );
         }
     } else if ctx.is_grouping() {
-        bail!("join error: must call summarize after a group_by");
+        bail!(
+            Type,
+            "join error: must call summarize or ungroup after a group_by"
+        );
     } else {
-        bail!("join error: missing input dataframe");
+        bail!(Type, "join error: missing input dataframe");
+    }
+
+    Ok(())
+}
+
+/// Evaluates an asof_join call.
+///
+/// Each input row is joined to the row of the other dataframe with the closest
+/// key before (`backward`), after (`forward`), or on either side (`nearest`).
+pub fn eval_asof(args: &[Expr], ctx: &mut Context) -> Result<()> {
+    let Some(lhs_df) = ctx.take_df() else {
+        if ctx.is_grouping() {
+            bail!(
+                Type,
+                "asof_join error: must call summarize or ungroup after a group_by"
+            );
+        } else {
+            bail!(Type, "asof_join error: missing input dataframe");
+        }
+    };
+
+    let rhs_df_name = args::identifier(&args[0])?;
+    let Some(rhs_df) = ctx.get_df(&rhs_df_name).cloned() else {
+        let hint = fuzzy::did_you_mean(&rhs_df_name, ctx.vars.keys().map(|v| v.as_str()));
+        bail!(
+            Schema,
+            "asof_join error: undefined dataframe variable '{rhs_df_name}'{hint}"
+        );
+    };
+
+    let lhs_schema = lhs_df
+        .schema()
+        .map_err(|e| Error::from_schema(e).context("asof_join error"))?;
+    let rhs_schema = rhs_df
+        .schema()
+        .map_err(|e| Error::from_schema(e).context("asof_join error"))?;
+
+    let mut options = AsOfOptions::default();
+    let mut keys = None;
+    let mut by = Vec::new();
+    let mut suffix = None;
+    let mut tolerance = None;
+
+    for arg in args.iter().skip(1) {
+        match arg {
+            Expr::BinaryOp(lhs, Operator::Eq, rhs) => {
+                keys = Some((args::identifier(lhs)?, args::identifier(rhs)?));
+            }
+            Expr::BinaryOp(lhs, Operator::Assign, rhs) => match args::identifier(lhs)?.as_str() {
+                "by" => {
+                    by = match rhs.as_ref() {
+                        Expr::List(columns) => columns
+                            .iter()
+                            .map(args::identifier)
+                            .collect::<Result<Vec<_>>>()?,
+                        column => vec![args::identifier(column)?],
+                    }
+                }
+                "strategy" => options.strategy = asof_strategy(rhs)?,
+                "tolerance" => match rhs.number() {
+                    Some(n) => tolerance = Some(n),
+                    None => options.tolerance_str = Some(asof_tolerance(rhs)?.into()),
+                },
+                "suffix" => suffix = Some(args::string(rhs)?),
+                name => {
+                    let named = ["by", "strategy", "tolerance", "suffix"];
+                    let hint = if lhs_schema.contains(name) {
+                        ", use '==' to match the join columns".to_string()
+                    } else {
+                        fuzzy::did_you_mean(name, named)
+                    };
+                    bail!(Type, "asof_join error: unknown argument '{name}'{hint}");
+                }
+            },
+            _ => {}
+        }
+    }
+
+    let Some((lhs_key, rhs_key)) = keys else {
+        bail!(
+            Schema,
+            "asof_join error: Missing join columns for '{rhs_df_name}'"
+        );
+    };
+
+    check_column("asof_join", &lhs_key, &lhs_schema)?;
+    check_column("asof_join", &rhs_key, &rhs_schema)?;
+    if lhs_schema.get(&lhs_key) != rhs_schema.get(&rhs_key) {
+        bail!(
+            Type,
+            "asof_join error: '{lhs_key}' and '{rhs_key}' don't have the same type"
+        );
+    }
+
+    if let Some(tolerance) = tolerance {
+        let dtype = lhs_schema.try_get(&lhs_key)?;
+        options.tolerance = Some(asof_number_tolerance(tolerance, &lhs_key, dtype)?);
+    }
+
+    if !by.is_empty() {
+        for column in &by {
+            check_column("asof_join", column, &lhs_schema)?;
+            check_column("asof_join", column, &rhs_schema)?;
+        }
+
+        let by = by.iter().map(|c| c.as_str().into()).collect::<Vec<_>>();
+        options.left_by = Some(by.clone());
+        options.right_by = Some(by);
+    }
+
+    // The keys must be sorted, the input rows order is restored after the join.
+    let row = "__dply_asof_row";
+    let lhs_df = lhs_df
+        .with_row_index(row, None)
+        .sort([&lhs_key], Default::default());
+    let rhs_df = rhs_df.sort([&rhs_key], Default::default());
+
+    let mut join_args = JoinArgs::new(JoinType::AsOf(options));
+    join_args.suffix = suffix;
+
+    let df = lhs_df
+        .join(rhs_df, [col(&lhs_key)], [col(&rhs_key)], join_args)
+        .sort([row], Default::default())
+        .drop([row]);
+
+    ctx.set_df(df)
+}
+
+fn asof_strategy(expr: &Expr) -> Result<AsofStrategy> {
+    match args::string(expr)?.as_str() {
+        "backward" => Ok(AsofStrategy::Backward),
+        "forward" => Ok(AsofStrategy::Forward),
+        "nearest" => Ok(AsofStrategy::Nearest),
+        s => bail!(
+            Type,
+            "asof_join error: invalid strategy '{s}', expected one of backward, forward, nearest"
+        ),
+    }
+}
+
+/// Casts a numeric tolerance to the key type, temporal keys use the number of
+/// time units.
+fn asof_number_tolerance(tolerance: f64, key: &str, dtype: &DataType) -> Result<AnyValue<'static>> {
+    if !dtype.is_numeric() && !dtype.is_temporal() {
+        bail!(
+            Type,
+            "asof_join error: tolerance requires a numeric or temporal key, found '{key}' ({dtype})"
+        );
+    }
+
+    let dtype = dtype.to_physical();
+    let integer = dtype.is_integer();
+    if tolerance < 0.0 || (integer && tolerance.fract() != 0.0) {
+        let expected = if integer {
+            "a non-negative integer"
+        } else {
+            "a non-negative number"
+        };
+        bail!(
+            Type,
+            "asof_join error: invalid tolerance {tolerance} for '{key}', expected {expected}"
+        );
+    }
+
+    let value = AnyValue::Float64(tolerance)
+        .strict_cast(&dtype)
+        .ok_or_else(|| err!(Type, "asof_join error: invalid tolerance {tolerance}"))?;
+
+    Ok(value.into_static()?)
+}
+
+/// Parses a tolerance duration like `"5m"` or `"1h30m"`.
+fn asof_tolerance(expr: &Expr) -> Result<String> {
+    let tolerance = args::string(expr)?;
+    let re = polars::export::regex::Regex::new(r"^(\d+(ns|us|ms|s|m|h|d|w))+$").unwrap();
+    if !re.is_match(&tolerance) {
+        bail!(
+            Type,
+            "asof_join error: invalid tolerance '{tolerance}', expected a duration like 30s, 5m, 1h30m, or 2d"
+        );
+    }
+
+    Ok(tolerance)
+}
+
+fn check_column(fname: &str, column: &str, schema: &Schema) -> Result<()> {
+    if !schema.contains(column) {
+        let hint = fuzzy::did_you_mean(column, schema.iter_names().map(|c| c.as_str()));
+        bail!(Schema, "{fname} error: Unknown column '{column}'{hint}");
     }
 
     Ok(())
 }
+
+/// Parses a `validate = "1:m"` argument.
+fn join_validation(expr: &Expr) -> Result<JoinValidation> {
+    match args::string(expr)?.as_str() {
+        "1:1" => Ok(JoinValidation::OneToOne),
+        "1:m" => Ok(JoinValidation::OneToMany),
+        "m:1" => Ok(JoinValidation::ManyToOne),
+        "m:m" => Ok(JoinValidation::ManyToMany),
+        s => bail!(
+            Type,
+            "join error: invalid validate value '{s}', expected one of 1:1, 1:m, m:1, m:m"
+        ),
+    }
+}
+
+/// Returns an error if the join keys of a dataframe are not unique.
+fn check_unique_keys(
+    df: &LazyFrame,
+    keys: &[PolarsExpr],
+    name: &str,
+    validation: &JoinValidation,
+) -> Result<()> {
+    let keys_df = df.clone().select(keys).collect()?;
+    let unique_df = keys_df.unique(None, UniqueKeepStrategy::Any, None)?;
+
+    if keys_df.height() != unique_df.height() {
+        bail!(
+            Engine,
+            "join error: duplicate keys in {name} are not allowed with {validation} validation"
+        );
+    }
+
+    Ok(())
+}
+
+const LHS_ROW: &str = "__dply_lhs_row";
+const RHS_ROW: &str = "__dply_rhs_row";
+
+/// Range join keys mapped to integers.
+type Keys = Vec<Option<i64>>;
+
+/// An inequality condition between a column of the input dataframe and a
+/// column of the other dataframe.
+struct Range {
+    lhs: String,
+    op: Operator,
+    rhs: String,
+}
+
+impl Range {
+    fn expr(&self, lhs: PolarsExpr, rhs: PolarsExpr) -> PolarsExpr {
+        match self.op {
+            Operator::Lt => lhs.lt(rhs),
+            Operator::LtEq => lhs.lt_eq(rhs),
+            Operator::Gt => lhs.gt(rhs),
+            _ => lhs.gt_eq(rhs),
+        }
+    }
+}
+
+/// Returns the sorted pairs of input and other dataframe rows indices that
+/// match the equality and the inequality conditions.
+///
+/// With equality conditions the rows are matched by a hash join and then
+/// filtered by the inequality conditions, otherwise the first two inequality
+/// conditions are matched by a sort based search.
+fn range_pairs(
+    lhs_df: &LazyFrame,
+    rhs_df: &LazyFrame,
+    (lhs_cols, rhs_cols): (Vec<PolarsExpr>, Vec<PolarsExpr>),
+    ranges: &[Range],
+    dry_run: bool,
+) -> Result<LazyFrame> {
+    let (pairs, matched) = if !lhs_cols.is_empty() {
+        let mut lhs_keys = vec![col(LHS_ROW)];
+        lhs_keys.extend(lhs_cols.iter().cloned());
+        let mut rhs_keys = vec![col(RHS_ROW)];
+        rhs_keys.extend(rhs_cols.iter().cloned());
+
+        let pairs = lhs_df
+            .clone()
+            .select(lhs_keys)
+            .join(
+                rhs_df.clone().select(rhs_keys),
+                lhs_cols,
+                rhs_cols,
+                JoinArgs::new(JoinType::Inner),
+            )
+            .select([col(LHS_ROW), col(RHS_ROW)]);
+
+        (pairs, 0)
+    } else if dry_run {
+        let pairs = DataFrame::new(vec![
+            Series::new_empty(LHS_ROW, &IDX_DTYPE),
+            Series::new_empty(RHS_ROW, &IDX_DTYPE),
+        ])?;
+
+        (pairs.lazy(), ranges.len())
+    } else {
+        let matched = ranges.len().min(2);
+        (range_search(lhs_df, rhs_df, &ranges[..matched])?, matched)
+    };
+
+    let pairs = if matched < ranges.len() {
+        let ranges = &ranges[matched..];
+        let lhs_keys = ranges
+            .iter()
+            .enumerate()
+            .map(|(idx, range)| col(&range.lhs).alias(&format!("__dply_lhs_{idx}")));
+        let rhs_keys = ranges
+            .iter()
+            .enumerate()
+            .map(|(idx, range)| col(&range.rhs).alias(&format!("__dply_rhs_{idx}")));
+
+        let predicate = ranges
+            .iter()
+            .enumerate()
+            .map(|(idx, range)| {
+                let lhs = col(&format!("__dply_lhs_{idx}"));
+                let rhs = col(&format!("__dply_rhs_{idx}"));
+                range.expr(lhs, rhs)
+            })
+            .reduce(|acc, e| acc.and(e))
+            .unwrap();
+
+        pairs
+            .join(
+                lhs_df.clone().select(
+                    [col(LHS_ROW)]
+                        .into_iter()
+                        .chain(lhs_keys)
+                        .collect::<Vec<_>>(),
+                ),
+                [col(LHS_ROW)],
+                [col(LHS_ROW)],
+                JoinArgs::new(JoinType::Inner),
+            )
+            .join(
+                rhs_df.clone().select(
+                    [col(RHS_ROW)]
+                        .into_iter()
+                        .chain(rhs_keys)
+                        .collect::<Vec<_>>(),
+                ),
+                [col(RHS_ROW)],
+                [col(RHS_ROW)],
+                JoinArgs::new(JoinType::Inner),
+            )
+            .filter(predicate)
+            .select([col(LHS_ROW), col(RHS_ROW)])
+    } else {
+        pairs
+    };
+
+    Ok(pairs.sort([LHS_ROW, RHS_ROW], Default::default()))
+}
+
+/// Finds the rows pairs that match one or two inequality conditions.
+///
+/// The other dataframe rows are sorted by the first condition key so that the
+/// rows matching an input row are in a contiguous range found by binary search,
+/// the rows in the range that match the second condition are found with a tree
+/// of the maximum keys that skips the sub ranges where no row can match.
+fn range_search(lhs_df: &LazyFrame, rhs_df: &LazyFrame, ranges: &[Range]) -> Result<LazyFrame> {
+    let lhs_keys = lhs_df
+        .clone()
+        .select(
+            ranges
+                .iter()
+                .enumerate()
+                .map(|(idx, r)| col(&r.lhs).alias(&format!("__dply_lhs_{idx}")))
+                .collect::<Vec<_>>(),
+        )
+        .collect()?;
+    let rhs_keys = rhs_df
+        .clone()
+        .select(
+            ranges
+                .iter()
+                .enumerate()
+                .map(|(idx, r)| col(&r.rhs).alias(&format!("__dply_rhs_{idx}")))
+                .collect::<Vec<_>>(),
+        )
+        .collect()?;
+
+    let mut keys = Vec::with_capacity(ranges.len());
+    for (lhs, rhs) in lhs_keys.get_columns().iter().zip(rhs_keys.get_columns()) {
+        keys.push(ordered_keys(lhs, rhs)?);
+    }
+
+    let (lhs_first, rhs_first) = &keys[0];
+    let mut order = (0..rhs_first.len())
+        .filter(|&idx| rhs_first[idx].is_some())
+        .collect::<Vec<_>>();
+    order.sort_by_key(|&idx| rhs_first[idx]);
+    let sorted = order.iter().map(|&idx| rhs_first[idx]).collect::<Vec<_>>();
+
+    // The second condition is normalized to `key > value` or `key >= value`,
+    // bitwise not reverses the keys order for `<` and `<=`.
+    let second = keys.get(1).map(|(lhs_keys, rhs_keys)| {
+        let reverse = matches!(ranges[1].op, Operator::Gt | Operator::GtEq);
+        let normalize = |key: Option<i64>| key.map(|k| if reverse { !k } else { k });
+        let tree = MaxTree::new(
+            &order
+                .iter()
+                .map(|&idx| normalize(rhs_keys[idx]))
+                .collect::<Vec<_>>(),
+        );
+        let strict = matches!(ranges[1].op, Operator::Lt | Operator::Gt);
+        (
+            lhs_keys.iter().map(|&k| normalize(k)).collect::<Vec<_>>(),
+            tree,
+            strict,
+        )
+    });
+
+    let mut lhs_rows = Vec::new();
+    let mut rhs_rows = Vec::new();
+
+    for (row, key) in lhs_first.iter().enumerate() {
+        let Some(key) = key else {
+            continue;
+        };
+
+        let range = match ranges[0].op {
+            Operator::Lt => sorted.partition_point(|k| *k <= Some(*key))..sorted.len(),
+            Operator::LtEq => sorted.partition_point(|k| *k < Some(*key))..sorted.len(),
+            Operator::Gt => 0..sorted.partition_point(|k| *k < Some(*key)),
+            _ => 0..sorted.partition_point(|k| *k <= Some(*key)),
+        };
+
+        let mut push = |pos: usize| {
+            lhs_rows.push(row as IdxSize);
+            rhs_rows.push(order[pos] as IdxSize);
+        };
+
+        match &second {
+            Some((lhs_keys, tree, strict)) => {
+                if let Some(value) = lhs_keys[row] {
+                    let accept = |max: i64| if *strict { max > value } else { max >= value };
+                    tree.find(range, &accept, &mut push);
+                }
+            }
+            None => range.for_each(push),
+        }
+    }
+
+    let pairs = DataFrame::new(vec![
+        Series::new(LHS_ROW, lhs_rows),
+        Series::new(RHS_ROW, rhs_rows),
+    ])?;
+
+    Ok(pairs.lazy())
+}
+
+/// Returns the keys of two comparable columns as integers with the same order.
+fn ordered_keys(lhs: &Series, rhs: &Series) -> Result<(Keys, Keys)> {
+    let is_integer = |s: &Series| s.dtype().is_integer() || s.dtype().is_temporal();
+
+    let keys = |s: &Series| -> Result<Keys> {
+        if is_integer(lhs) && is_integer(rhs) {
+            let s = s.to_physical_repr().cast(&DataType::Int64)?;
+            Ok(s.i64()?.into_iter().collect())
+        } else {
+            // Maps floats to integers with the same order, -0.0 and 0.0 have
+            // different bits but are equal.
+            let s = s.cast(&DataType::Float64)?;
+            Ok(s.f64()?
+                .into_iter()
+                .map(|v| {
+                    v.map(|v| {
+                        let v = if v == 0.0 { 0.0 } else { v };
+                        let bits = v.to_bits() as i64;
+                        if bits < 0 {
+                            bits ^ i64::MAX
+                        } else {
+                            bits
+                        }
+                    })
+                })
+                .collect())
+        }
+    };
+
+    Ok((keys(lhs)?, keys(rhs)?))
+}
+
+/// A segment tree with the maximum key of each sub range.
+struct MaxTree {
+    size: usize,
+    nodes: Vec<Option<i64>>,
+}
+
+impl MaxTree {
+    fn new(keys: &[Option<i64>]) -> Self {
+        let size = keys.len().next_power_of_two();
+        let mut nodes = vec![None; 2 * size];
+        nodes[size..size + keys.len()].copy_from_slice(keys);
+        for idx in (1..size).rev() {
+            nodes[idx] = nodes[2 * idx].max(nodes[2 * idx + 1]);
+        }
+
+        Self { size, nodes }
+    }
+
+    /// Calls `found` with the positions in `range` with an accepted key, `accept`
+    /// must be true for all the keys greater than an accepted key.
+    fn find(
+        &self,
+        range: std::ops::Range<usize>,
+        accept: &impl Fn(i64) -> bool,
+        found: &mut impl FnMut(usize),
+    ) {
+        self.visit(1, 0..self.size, &range, accept, found);
+    }
+
+    fn visit(
+        &self,
+        node: usize,
+        span: std::ops::Range<usize>,
+        range: &std::ops::Range<usize>,
+        accept: &impl Fn(i64) -> bool,
+        found: &mut impl FnMut(usize),
+    ) {
+        if span.end <= range.start || span.start >= range.end {
+            return;
+        }
+
+        if !self.nodes[node].is_some_and(accept) {
+            return;
+        }
+
+        if span.len() == 1 {
+            found(span.start);
+        } else {
+            let mid = (span.start + span.end) / 2;
+            self.visit(2 * node, span.start..mid, range, accept, found);
+            self.visit(2 * node + 1, mid..span.end, range, accept, found);
+        }
+    }
+}
+
+/// Joins the input and the other dataframe rows using the matching rows pairs.
+///
+/// The equality keys of the other dataframe are coalesced into the input keys
+/// like in a hash join, the other columns with the same name as an input column
+/// get the join suffix.
+fn join_pairs(
+    lhs_df: LazyFrame,
+    rhs_df: LazyFrame,
+    pairs: LazyFrame,
+    equal_keys: &[(String, String)],
+    join_args: &JoinArgs,
+) -> Result<LazyFrame> {
+    let lhs_schema = lhs_df.schema().map_err(Error::from_schema)?;
+    let rhs_schema = rhs_df.schema().map_err(Error::from_schema)?;
+    let suffix = join_args.suffix();
+
+    let mut rhs_columns = Vec::with_capacity(rhs_schema.len());
+    let mut unmatched_columns = Vec::with_capacity(rhs_schema.len());
+    for c in rhs_schema.iter_names() {
+        if let Some((lhs_key, _)) = equal_keys.iter().find(|(_, rhs_key)| rhs_key == c) {
+            // Unmatched rows of an outer join keep their key.
+            unmatched_columns.push(col(c).alias(lhs_key));
+        } else if c != RHS_ROW && lhs_schema.contains(c) {
+            let column = col(c).alias(&format!("{c}{suffix}"));
+            rhs_columns.push(column.clone());
+            unmatched_columns.push(column);
+        } else {
+            rhs_columns.push(col(c));
+            unmatched_columns.push(col(c));
+        }
+    }
+    let unmatched_df = rhs_df.clone().select(unmatched_columns);
+    let rhs_df = rhs_df.select(rhs_columns);
+
+    let by_lhs_row = || ([col(LHS_ROW)], [col(LHS_ROW)]);
+    let by_rhs_row = || ([col(RHS_ROW)], [col(RHS_ROW)]);
+
+    let df = match join_args.how {
+        JoinType::Semi | JoinType::Anti => {
+            let (lhs_on, rhs_on) = by_lhs_row();
+            lhs_df.join(pairs, lhs_on, rhs_on, JoinArgs::new(join_args.how.clone()))
+        }
+        JoinType::Inner | JoinType::Cross => {
+            let (lhs_on, rhs_on) = by_lhs_row();
+            let df = pairs.join(lhs_df, lhs_on, rhs_on, JoinArgs::new(JoinType::Left));
+            let (lhs_on, rhs_on) = by_rhs_row();
+            df.join(rhs_df, lhs_on, rhs_on, JoinArgs::new(JoinType::Left))
+        }
+        _ => {
+            let (lhs_on, rhs_on) = by_lhs_row();
+            let df = lhs_df.join(pairs.clone(), lhs_on, rhs_on, JoinArgs::new(JoinType::Left));
+            let (lhs_on, rhs_on) = by_rhs_row();
+            let df = df.join(rhs_df, lhs_on, rhs_on, JoinArgs::new(JoinType::Left));
+
+            if let JoinType::Outer = join_args.how {
+                // The other dataframe rows without a match are added at the end.
+                let (lhs_on, rhs_on) = by_rhs_row();
+                let unmatched =
+                    unmatched_df.join(pairs, lhs_on, rhs_on, JoinArgs::new(JoinType::Anti));
+                concat_lf_diagonal([df, unmatched], Default::default())?
+            } else {
+                df
+            }
+        }
+    };
+
+    Ok(df.select([all().exclude([LHS_ROW, RHS_ROW])]))
+}
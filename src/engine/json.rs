@@ -1,9 +1,9 @@
 // Copyright (C) 2023 Vince Vasta
 // SPDX-License-Identifier: Apache-2.0
-use anyhow::{anyhow, bail, Result};
 use polars::prelude::*;
 use std::path::PathBuf;
 
+use crate::error::{bail, err, Result};
 use crate::parser::Expr;
 
 use super::*;
@@ -12,17 +12,26 @@ use super::*;
 ///
 /// Parameters are checked before evaluation by the typing module.
 pub fn eval(args: &[Expr], ctx: &mut Context) -> Result<()> {
-    let path = PathBuf::from(args::string(&args[0]));
+    let path = PathBuf::from(args::string(&args[0])?);
     let overwrite = args::named_bool(args, "overwrite")?;
 
     // If there is an input dataframe save it to disk.
     if let Some(df) = ctx.take_df() {
         if !overwrite && path.exists() {
-            bail!("json error: file '{}' already exists.", path.display());
+            bail!(Io, "json error: file '{}' already exists.", path.display());
         }
 
-        let file = std::fs::File::create(&path)
-            .map_err(|e| anyhow!("parquet error: cannot create file '{}' {e}", path.display()))?;
+        if ctx.is_dry_run() {
+            return ctx.dry_run_write(&path, df);
+        }
+
+        let file = std::fs::File::create(&path).map_err(|e| {
+            err!(
+                Io,
+                "parquet error: cannot create file '{}' {e}",
+                path.display()
+            )
+        })?;
 
         let mut out_df = df.clone().collect()?;
         ctx.set_df(df)?;
@@ -30,12 +39,14 @@ pub fn eval(args: &[Expr], ctx: &mut Context) -> Result<()> {
         JsonWriter::new(file)
             .with_json_format(JsonFormat::JsonLines)
             .finish(&mut out_df)?;
+    } else if let Some(df) = ctx.dry_run_read(&path) {
+        ctx.set_df(df)?;
     } else {
         // Read the data frame and set it as input for the next task.
         let df = LazyJsonLineReader::new(&path)
             .with_infer_schema_length(Some(1000))
             .finish()
-            .map_err(|e| anyhow!("json error: cannot read file '{}' {e}", path.display()))?;
+            .map_err(|e| err!(Io, "json error: cannot read file '{}' {e}", path.display()))?;
         ctx.set_df(df)?;
     }
 
@@ -12,11 +12,11 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
-use anyhow::{bail, Result};
-use polars::lazy::dsl::{duration, DurationArgs, Expr as PolarsExpr, StrptimeOptions};
+use polars::lazy::dsl::Expr as PolarsExpr;
 use polars::prelude::*;
 use std::collections::HashSet;
 
+use crate::error::{bail, Result};
 use crate::parser::{Expr, Operator};
 
 use super::*;
@@ -25,144 +25,49 @@ use super::*;
 ///
 /// Parameters are checked before evaluation by the typing module.
 pub fn eval(args: &[Expr], ctx: &mut Context) -> Result<()> {
-    if let Some(mut df) = ctx.take_df() {
-        let mut used_aliases = HashSet::new();
-
-        for arg in args {
-            match arg {
-                Expr::BinaryOp(lhs, Operator::Assign, rhs) => {
-                    let alias = args::identifier(lhs);
-                    if used_aliases.contains(&alias) {
-                        bail!("mutate error: duplicate alias '{alias}'");
-                    } else {
-                        used_aliases.insert(alias.clone());
-                    }
-
-                    let expr = df
-                        .schema()
-                        .map_err(anyhow::Error::from)
-                        .and_then(|schema| eval_expr(rhs, &schema))
-                        .map_err(|e| anyhow!("mutate error: {e}"))?;
-                    df = df.with_column(expr.alias(&alias));
-                }
-                _ => panic!("Unexpected mutate expression: {arg}"),
-            }
-        }
-
+    if let Some(df) = ctx.take_df() {
+        let df = eval_args(args, df, &[])?;
         ctx.set_df(df)?;
-    } else if ctx.is_grouping() {
-        bail!("mutate error: must call summarize after a group_by");
+    } else if let Some((df, keys)) = ctx.take_group_df() {
+        // Grouped expressions are evaluated over each group keeping all rows.
+        let partition = keys.iter().map(|k| col(k)).collect::<Vec<_>>();
+        let df = eval_args(args, df, &partition)?;
+        ctx.set_group(df.group_by_stable(&partition), keys)?;
     } else {
-        bail!("mutate error: missing input dataframe");
+        bail!(Type, "mutate error: missing input dataframe");
     }
 
     Ok(())
 }
 
-fn eval_expr(expr: &Expr, schema: &Schema) -> Result<PolarsExpr> {
-    match expr {
-        Expr::BinaryOp(lhs, op, rhs) => {
-            let lhs = eval_expr(lhs, schema)?;
-            let rhs = eval_expr(rhs, schema)?;
+fn eval_args(args: &[Expr], mut df: LazyFrame, partition: &[PolarsExpr]) -> Result<LazyFrame> {
+    let mut used_aliases = HashSet::new();
 
-            let result = match op {
-                Operator::Plus => lhs + rhs,
-                Operator::Minus => lhs - rhs,
-                Operator::Multiply => lhs * rhs,
-                Operator::Divide => lhs / rhs,
-                Operator::Mod => lhs % rhs.cast(DataType::UInt64),
-                _ => panic!("Unexpected mutate operator {op}"),
-            };
+    for arg in args {
+        match arg {
+            Expr::BinaryOp(lhs, Operator::Assign, rhs) => {
+                let alias = args::identifier(lhs)?;
+                if used_aliases.contains(&alias) {
+                    bail!(Type, "mutate error: duplicate alias '{alias}'");
+                } else {
+                    used_aliases.insert(alias.clone());
+                }
 
-            Ok(result)
-        }
-        Expr::Identifier(_) => args::column(expr, schema),
-        Expr::String(s) => Ok(lit(s.clone())),
-        Expr::Number(n) => Ok(lit(*n)),
-        Expr::Function(name, args) if name == "ymd_hms" => {
-            args::column(&args[0], schema).map(|c| {
-                c.str().to_datetime(
-                    Some(TimeUnit::Nanoseconds),
-                    None,
-                    StrptimeOptions::default(),
-                    lit("raise"),
-                )
-            })
-        }
-        Expr::Function(name, args) if name == "dnanos" => args::column(&args[0], schema).map(|c| {
-            duration(DurationArgs {
-                nanoseconds: c,
-                ..Default::default()
-            })
-        }),
-        Expr::Function(name, args) if name == "dmicros" => {
-            args::column(&args[0], schema).map(|c| {
-                duration(DurationArgs {
-                    microseconds: c,
-                    ..Default::default()
-                })
-            })
-        }
-        Expr::Function(name, args) if name == "dmillis" => {
-            args::column(&args[0], schema).map(|c| {
-                duration(DurationArgs {
-                    milliseconds: c,
-                    ..Default::default()
-                })
-            })
-        }
-        Expr::Function(name, args) if name == "dsecs" => args::column(&args[0], schema).map(|c| {
-            duration(DurationArgs {
-                seconds: c,
-                ..Default::default()
-            })
-        }),
-        Expr::Function(name, args) if name == "nanos" => {
-            args::column(&args[0], schema).map(|c| c.dt().total_nanoseconds())
-        }
-        Expr::Function(name, args) if name == "micros" => {
-            args::column(&args[0], schema).map(|c| c.dt().total_microseconds())
-        }
-        Expr::Function(name, args) if name == "millis" => {
-            args::column(&args[0], schema).map(|c| c.dt().total_milliseconds())
-        }
-        Expr::Function(name, args) if name == "secs" => {
-            args::column(&args[0], schema).map(|c| c.dt().total_seconds())
-        }
-        Expr::Function(name, args) if name == "field" => {
-            let field_name = args::identifier(&args[1]);
-            args::column(&args[0], schema).map(|c| c.struct_().field_by_name(&field_name))
-        }
-        Expr::Function(name, args) if name == "mean" => {
-            args::column(&args[0], schema).map(|c| c.mean())
-        }
-        Expr::Function(name, args) if name == "median" => {
-            args::column(&args[0], schema).map(|c| c.median())
-        }
-        Expr::Function(name, args) if name == "min" => {
-            args::column(&args[0], schema).map(|c| c.min())
-        }
-        Expr::Function(name, args) if name == "max" => {
-            args::column(&args[0], schema).map(|c| c.max())
-        }
-        Expr::Function(name, args) if name == "len" => {
-            let column = args::identifier(&args[0]);
-            match schema.get(&column) {
-                Some(DataType::List(_)) => Ok(col(&column).list().len()),
-                Some(DataType::String) => Ok(col(&column).str().len_chars()),
-                Some(_) => Err(anyhow!("`len` column '{column}' must be list or String")),
-                None => Err(anyhow!("Unknown column '{column}'")),
+                let mut expr = df
+                    .schema()
+                    .map_err(Error::from_schema)
+                    .and_then(|schema| expression::eval(rhs, &schema))
+                    .map_err(|e| e.context("mutate error"))?;
+
+                if !partition.is_empty() {
+                    expr = expr.over(partition);
+                }
+
+                df = df.with_column(expr.alias(&alias));
             }
+            _ => bail!(Type, "Unexpected mutate expression: {arg}"),
         }
-        Expr::Function(name, _args) if name == "row" => {
-            let (col_name, _) = schema
-                .get_at_index(0)
-                .ok_or_else(|| anyhow!("No columns found"))?;
-            Ok(col(col_name).map(
-                |s| Ok(Some(Series::from_iter(1..=(s.len() as u64)))),
-                GetOutput::from_type(DataType::UInt64),
-            ))
-        }
-        _ => panic!("Unexpected mutate expression {expr}"),
     }
+
+    Ok(df)
 }
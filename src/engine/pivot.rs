@@ -0,0 +1,219 @@
+// Copyright (C) 2023 Vince Vasta
+// SPDX-License-Identifier: Apache-2.0
+use polars::lazy::dsl::Expr as PolarsExpr;
+use polars::lazy::frame::pivot::pivot_stable;
+use polars::prelude::*;
+
+use crate::error::{bail, Result};
+use crate::parser::{Expr, Operator};
+
+use super::*;
+
+/// Temporary index column used when pivot_wider has no index columns.
+const PIVOT_INDEX: &str = "__dply_pivot_index";
+
+/// Evaluates a pivot_longer call.
+///
+/// Parameters are checked before evaluation by the typing module.
+pub fn eval_longer(args: &[Expr], ctx: &mut Context) -> Result<()> {
+    if let Some(df) = ctx.take_df() {
+        let schema_cols = ctx.columns();
+        let mut value_vars: Vec<String> = Vec::new();
+        let mut names_to = "name".to_string();
+        let mut values_to = "value".to_string();
+
+        for arg in args {
+            let columns = match arg {
+                Expr::BinaryOp(lhs, Operator::Assign, rhs) => {
+                    match args::identifier(lhs)?.as_str() {
+                        "names_to" => names_to = args::identifier(rhs)?,
+                        "values_to" => values_to = args::identifier(rhs)?,
+                        _ => {}
+                    }
+                    continue;
+                }
+                Expr::Identifier(column) => {
+                    if !schema_cols.contains(column) {
+                        let hint =
+                            fuzzy::did_you_mean(column, schema_cols.iter().map(|c| c.as_str()));
+                        bail!(
+                            Schema,
+                            "pivot_longer error: Unknown column '{column}'{hint}"
+                        );
+                    }
+                    vec![column.to_owned()]
+                }
+                Expr::UnaryOp(Operator::Not, expr) => {
+                    select::filter_columns(expr, schema_cols, true)?
+                }
+                _ => select::filter_columns(arg, schema_cols, false)?,
+            };
+
+            for column in columns {
+                if !value_vars.contains(&column) {
+                    value_vars.push(column);
+                }
+            }
+        }
+
+        if value_vars.is_empty() {
+            bail!(Schema, "pivot_longer error: no columns to pivot");
+        }
+
+        let id_vars = schema_cols
+            .iter()
+            .filter(|c| !value_vars.contains(c))
+            .map(|c| c.as_str().into())
+            .collect();
+
+        let df = df.melt(MeltArgs {
+            id_vars,
+            value_vars: value_vars.iter().map(|c| c.as_str().into()).collect(),
+            variable_name: Some(names_to.into()),
+            value_name: Some(values_to.into()),
+            streamable: false,
+        });
+
+        ctx.set_df(df)?;
+    } else if ctx.is_grouping() {
+        bail!(
+            Type,
+            "pivot_longer error: must call summarize or ungroup after a group_by"
+        );
+    } else {
+        bail!(Type, "pivot_longer error: missing input dataframe");
+    }
+
+    Ok(())
+}
+
+/// Evaluates a pivot_wider call.
+///
+/// The output columns depend on the values in the `names_from` column, so
+/// during a dry run the rest of the script cannot be checked.
+pub fn eval_wider(args: &[Expr], ctx: &mut Context) -> Result<()> {
+    if let Some(df) = ctx.take_df() {
+        let schema_cols = ctx.columns();
+        let mut names_from = None;
+        let mut values_from = None;
+        let mut values_fn = col("").first();
+        let mut values_fill = None;
+
+        for arg in args {
+            if let Expr::BinaryOp(lhs, Operator::Assign, rhs) = arg {
+                match args::identifier(lhs)?.as_str() {
+                    "names_from" => names_from = Some(args::identifier(rhs)?),
+                    "values_from" => values_from = Some(args::identifier(rhs)?),
+                    "values_fn" => values_fn = eval_values_fn(rhs)?,
+                    "values_fill" => values_fill = Some(rhs.as_ref()),
+                    _ => {}
+                }
+            }
+        }
+
+        let (Some(names_from), Some(values_from)) = (names_from, values_from) else {
+            bail!(
+                Type,
+                "pivot_wider error: missing names_from or values_from argument"
+            );
+        };
+
+        for column in [&names_from, &values_from] {
+            if !schema_cols.contains(column) {
+                let hint = fuzzy::did_you_mean(column, schema_cols.iter().map(|c| c.as_str()));
+                bail!(Schema, "pivot_wider error: Unknown column '{column}'{hint}");
+            }
+        }
+
+        let mut index = schema_cols
+            .iter()
+            .filter(|c| **c != names_from && **c != values_from)
+            .cloned()
+            .collect::<Vec<_>>();
+
+        if ctx.is_dry_run() {
+            ctx.set_unknown_schema();
+            return Ok(());
+        }
+
+        // Polars needs an index column, pivot into a single row when all the
+        // columns are used by names_from and values_from.
+        let no_index = index.is_empty();
+        let df = if no_index {
+            index.push(PIVOT_INDEX.to_string());
+            df.with_column(lit(0).alias(PIVOT_INDEX))
+        } else {
+            df
+        };
+
+        let df = df.collect()?;
+        let mut df = pivot_stable(
+            &df,
+            &index,
+            [&names_from],
+            Some([&values_from]),
+            false,
+            Some(values_fn),
+            None,
+        )?;
+
+        if no_index {
+            df = df.drop(PIVOT_INDEX)?;
+        }
+
+        let df = if let Some(value) = values_fill {
+            let fills = df
+                .get_columns()
+                .iter()
+                .filter(|s| !index.iter().any(|c| c == s.name()))
+                .map(|s| fill_expr(s.name(), s.dtype(), value))
+                .collect::<Vec<_>>();
+            df.lazy().with_columns(fills)
+        } else {
+            df.lazy()
+        };
+
+        ctx.set_df(df)?;
+    } else if ctx.is_grouping() {
+        bail!(
+            Type,
+            "pivot_wider error: must call summarize or ungroup after a group_by"
+        );
+    } else {
+        bail!(Type, "pivot_wider error: missing input dataframe");
+    }
+
+    Ok(())
+}
+
+fn eval_values_fn(expr: &Expr) -> Result<PolarsExpr> {
+    let values = col("");
+    let agg = match args::identifier(expr)?.as_str() {
+        "count" => values.count(),
+        "first" => values.first(),
+        "last" => values.last(),
+        "max" => values.max(),
+        "mean" => values.mean(),
+        "median" => values.median(),
+        "min" => values.min(),
+        "sum" => values.sum(),
+        name => bail!(
+            Type,
+            "pivot_wider error: invalid values_fn '{name}', expected one of count, first, last, max, mean, median, min, or sum"
+        ),
+    };
+
+    Ok(agg)
+}
+
+fn fill_expr(name: &str, dtype: &DataType, value: &Expr) -> PolarsExpr {
+    let fill = match (value.number(), value) {
+        // Keep integer columns as integers when filling with a number.
+        (Some(n), _) if dtype.is_numeric() => lit(n).cast(dtype.clone()),
+        (Some(n), _) => lit(n),
+        (None, Expr::String(s)) => lit(s.clone()),
+        _ => lit(NULL),
+    };
+
+    col(name).fill_null(fill)
+}
@@ -1,8 +1,8 @@
 // Copyright (C) 2023 Vince Vasta
 // SPDX-License-Identifier: Apache-2.0
-use anyhow::{bail, Result};
 use polars::prelude::*;
 
+use crate::error::{bail, Result};
 use crate::parser::{Expr, Operator};
 
 use super::*;
@@ -21,7 +21,7 @@ enum RelocateTo {
 ///
 /// Parameters are checked before evaluation by the typing module.
 pub fn eval(args: &[Expr], ctx: &mut Context) -> Result<()> {
-    if let Some(df) = ctx.take_df() {
+    if let Some((df, keys)) = ctx.take_df_or_group() {
         let schema_cols = ctx.columns();
         let mut relocate_cols = Vec::<&str>::new();
         let mut relocate_to = RelocateTo::Default;
@@ -30,11 +30,13 @@ pub fn eval(args: &[Expr], ctx: &mut Context) -> Result<()> {
             match arg {
                 Expr::BinaryOp(lhs, Operator::Assign, rhs) => {
                     // before or after
-                    let dest = args::identifier(lhs);
-                    let pos = args::identifier(rhs);
+                    let dest = args::identifier(lhs)?;
+                    let pos = args::identifier(rhs)?;
 
                     if !schema_cols.contains(&pos) {
-                        bail!("relocate error: Unknown {dest} column {pos}");
+                        let hint =
+                            fuzzy::did_you_mean(&pos, schema_cols.iter().map(|c| c.as_str()));
+                        bail!(Schema, "relocate error: Unknown {dest} column {pos}{hint}");
                     }
 
                     relocate_to = if dest == "before" {
@@ -45,7 +47,9 @@ pub fn eval(args: &[Expr], ctx: &mut Context) -> Result<()> {
                 }
                 Expr::Identifier(column) => {
                     if !schema_cols.contains(column) {
-                        bail!("relocate error: Unknown column {column}");
+                        let hint =
+                            fuzzy::did_you_mean(column, schema_cols.iter().map(|c| c.as_str()));
+                        bail!(Schema, "relocate error: Unknown column '{column}'{hint}");
                     }
 
                     if !relocate_cols.contains(&column.as_str()) {
@@ -78,11 +82,9 @@ pub fn eval(args: &[Expr], ctx: &mut Context) -> Result<()> {
         };
 
         let columns = schema_cols.into_iter().map(col).collect::<Vec<_>>();
-        ctx.set_df(df.select(&columns))?;
-    } else if ctx.is_grouping() {
-        bail!("relocate error: must call summarize after a group_by");
+        ctx.set_df_or_group(df.select(&columns), keys)?;
     } else {
-        bail!("relocate error: missing input dataframe");
+        bail!(Type, "relocate error: missing input dataframe");
     }
 
     Ok(())
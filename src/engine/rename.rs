@@ -1,8 +1,8 @@
 // Copyright (C) 2023 Vince Vasta
 // SPDX-License-Identifier: Apache-2.0
-use anyhow::{bail, Result};
 use polars::prelude::*;
 
+use crate::error::{bail, Result};
 use crate::parser::{Expr, Operator};
 
 use super::*;
@@ -11,27 +11,31 @@ use super::*;
 ///
 /// Parameters are checked before evaluation by the typing module.
 pub fn eval(args: &[Expr], ctx: &mut Context) -> Result<()> {
-    if let Some(df) = ctx.take_df() {
+    if let Some((df, mut keys)) = ctx.take_df_or_group() {
         let mut schema_cols = ctx.columns().iter().map(|c| col(c)).collect::<Vec<_>>();
 
         for arg in args {
             if let Expr::BinaryOp(lhs, Operator::Assign, rhs) = arg {
-                let alias = args::identifier(lhs);
-                let column = args::identifier(rhs);
+                let alias = args::identifier(lhs)?;
+                let column = args::identifier(rhs)?;
 
                 if let Some(idx) = schema_cols.iter().position(|c| c == &col(&column)) {
                     schema_cols[idx] = schema_cols[idx].clone().alias(&alias);
+
+                    for key in keys.iter_mut().flatten().filter(|k| **k == column) {
+                        key.clone_from(&alias);
+                    }
                 } else {
-                    bail!("rename error: Unknown column {column}");
+                    let hint =
+                        fuzzy::did_you_mean(&column, ctx.columns().iter().map(|c| c.as_str()));
+                    bail!(Schema, "rename error: Unknown column '{column}'{hint}");
                 }
             }
         }
 
-        ctx.set_df(df.select(&schema_cols))?;
-    } else if ctx.is_grouping() {
-        bail!("rename error: must call summarize after a group_by");
+        ctx.set_df_or_group(df.select(&schema_cols), keys)?;
     } else {
-        bail!("rename error: missing input dataframe");
+        bail!(Type, "rename error: missing input dataframe");
     }
 
     Ok(())
@@ -1,9 +1,9 @@
 // Copyright (C) 2023 Vince Vasta
 // SPDX-License-Identifier: Apache-2.0
-use anyhow::{bail, Result};
 use polars::lazy::dsl::Expr as PolarsExpr;
 use polars::prelude::*;
 
+use crate::error::{bail, Result};
 use crate::parser::{Expr, Operator};
 
 use super::*;
@@ -12,26 +12,24 @@ use super::*;
 ///
 /// Parameters are checked before evaluation by the typing module.
 pub fn eval(args: &[Expr], ctx: &mut Context) -> Result<()> {
-    if let Some(df) = ctx.take_df() {
+    if let Some((df, keys)) = ctx.take_df_or_group() {
         let schema_cols = ctx.columns();
         let mut select_columns = Vec::new();
 
         for arg in args {
             match arg {
                 Expr::Function(_, _) => {
-                    let mut filter_cols = filter_columns(arg, schema_cols, false);
-                    filter_cols.retain(|e| !select_columns.contains(e));
-                    select_columns.extend(filter_cols);
+                    let filter_cols = filter_columns(arg, schema_cols, false)?;
+                    extend_columns(&mut select_columns, filter_cols);
                 }
                 Expr::UnaryOp(Operator::Not, expr) => {
-                    let mut filter_cols = filter_columns(expr, schema_cols, true);
-                    filter_cols.retain(|e| !select_columns.contains(e));
-                    select_columns.extend(filter_cols);
+                    let filter_cols = filter_columns(expr, schema_cols, true)?;
+                    extend_columns(&mut select_columns, filter_cols);
                 }
                 Expr::BinaryOp(lhs, Operator::Assign, rhs) => {
                     // select(alias = column)
-                    let alias = args::identifier(lhs);
-                    let column = args::identifier(rhs);
+                    let alias = args::identifier(lhs)?;
+                    let column = args::identifier(rhs)?;
                     let expr = col(&column).alias(&alias);
 
                     if !select_columns.contains(&expr) {
@@ -41,7 +39,9 @@ pub fn eval(args: &[Expr], ctx: &mut Context) -> Result<()> {
                 Expr::Identifier(column) => {
                     // select(column)
                     if !schema_cols.contains(column) {
-                        bail!("select error: Unknown column {column}");
+                        let hint =
+                            fuzzy::did_you_mean(column, schema_cols.iter().map(|c| c.as_str()));
+                        bail!(Schema, "select error: Unknown column '{column}'{hint}");
                     }
 
                     let expr = col(column);
@@ -53,45 +53,65 @@ pub fn eval(args: &[Expr], ctx: &mut Context) -> Result<()> {
             }
         }
 
-        ctx.set_df(df.select(&select_columns))?;
-    } else if ctx.is_grouping() {
-        bail!("select error: must call summarize after a group_by");
+        // Group columns are always kept.
+        for key in keys.iter().flatten().rev() {
+            let expr = col(key);
+            if !select_columns.contains(&expr) {
+                select_columns.insert(0, expr);
+            }
+        }
+
+        ctx.set_df_or_group(df.select(&select_columns), keys)?;
     } else {
-        bail!("select error: missing input dataframe");
+        bail!(Type, "select error: missing input dataframe");
     }
 
     Ok(())
 }
 
-fn filter_columns(expr: &Expr, schema_cols: &[String], negate: bool) -> Vec<PolarsExpr> {
+fn extend_columns(select_columns: &mut Vec<PolarsExpr>, columns: Vec<String>) {
+    for column in columns {
+        let expr = col(&column);
+        if !select_columns.contains(&expr) {
+            select_columns.push(expr);
+        }
+    }
+}
+
+/// Returns the schema columns that match a `starts_with`, `ends_with`, or
+/// `contains` selector, or the columns that don't match if `negate` is true.
+pub fn filter_columns(expr: &Expr, schema_cols: &[String], negate: bool) -> Result<Vec<String>> {
     match expr {
         Expr::Function(name, args) if name == "starts_with" => {
             // select(starts_with("pattern"))
-            let pattern = args::string(&args[0]);
-            schema_cols
+            let pattern = args::string(&args[0])?;
+            let columns = schema_cols
                 .iter()
                 .filter(|c| c.starts_with(&pattern) ^ negate)
-                .map(|c| col(c))
-                .collect()
+                .cloned()
+                .collect();
+            Ok(columns)
         }
         Expr::Function(name, args) if name == "ends_with" => {
             // select(ends_with("pattern"))
-            let pattern = args::string(&args[0]);
-            schema_cols
+            let pattern = args::string(&args[0])?;
+            let columns = schema_cols
                 .iter()
                 .filter(|c| c.ends_with(&pattern) ^ negate)
-                .map(|c| col(c))
-                .collect()
+                .cloned()
+                .collect();
+            Ok(columns)
         }
         Expr::Function(name, args) if name == "contains" => {
             // select(contains("pattern"))
-            let pattern = args::string(&args[0]);
-            schema_cols
+            let pattern = args::string(&args[0])?;
+            let columns = schema_cols
                 .iter()
                 .filter(|c| c.contains(&pattern) ^ negate)
-                .map(|c| col(c))
-                .collect()
+                .cloned()
+                .collect();
+            Ok(columns)
         }
-        _ => Vec::new(),
+        _ => Ok(Vec::new()),
     }
 }
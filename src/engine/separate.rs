@@ -0,0 +1,105 @@
+// Copyright (C) 2023 Vince Vasta
+// SPDX-License-Identifier: Apache-2.0
+use polars::export::regex::Regex;
+use polars::lazy::dsl::Expr as PolarsExpr;
+use polars::prelude::*;
+
+use crate::error::{bail, err, Result};
+use crate::parser::{Expr, Operator};
+
+use super::*;
+
+/// Evaluates a separate call.
+///
+/// Parameters are checked before evaluation by the typing module.
+pub fn eval(args: &[Expr], ctx: &mut Context) -> Result<()> {
+    if let Some(df) = ctx.take_df() {
+        let schema_cols = ctx.columns();
+        let column = args::identifier(&args[0])?;
+        if !schema_cols.contains(&column) {
+            let hint = fuzzy::did_you_mean(&column, schema_cols.iter().map(|c| c.as_str()));
+            bail!(Schema, "separate error: Unknown column '{column}'{hint}");
+        }
+
+        // Same default as dplyr, splits on any sequence of non alphanumeric chars.
+        let mut sep = "[^[:alnum:]]+".to_string();
+        let mut into = Vec::new();
+        let mut remove = true;
+
+        for arg in &args[1..] {
+            if let Expr::BinaryOp(lhs, Operator::Assign, rhs) = arg {
+                match args::identifier(lhs)?.as_str() {
+                    "into" => match rhs.as_ref() {
+                        Expr::List(names) => {
+                            into = names
+                                .iter()
+                                .map(args::identifier)
+                                .collect::<Result<Vec<_>>>()?;
+                        }
+                        name => into = vec![args::identifier(name)?],
+                    },
+                    "sep" => sep = args::string(rhs)?,
+                    "remove" => {
+                        remove =
+                            args::bool("remove", rhs).map_err(|e| e.context("separate error"))?
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        if into.is_empty() {
+            bail!(Type, "separate error: missing into argument");
+        }
+
+        let sep =
+            Regex::new(&sep).map_err(|_| err!(Type, "separate error: invalid regex '{sep}'"))?;
+
+        let pieces = into
+            .iter()
+            .enumerate()
+            .map(|(idx, name)| piece(&column, &sep, idx).alias(name))
+            .collect::<Vec<_>>();
+
+        let mut columns = Vec::with_capacity(schema_cols.len() + pieces.len());
+        for c in schema_cols {
+            if *c == column {
+                if !remove && !into.contains(c) {
+                    columns.push(col(c));
+                }
+                columns.extend(pieces.iter().cloned());
+            } else if !into.contains(c) {
+                columns.push(col(c));
+            }
+        }
+
+        ctx.set_df(df.select(&columns))?;
+    } else if ctx.is_grouping() {
+        bail!(
+            Type,
+            "separate error: must call summarize or ungroup after a group_by"
+        );
+    } else {
+        bail!(Type, "separate error: missing input dataframe");
+    }
+
+    Ok(())
+}
+
+/// Returns the piece at `idx` after splitting the column values, missing pieces
+/// are set to null.
+fn piece(column: &str, sep: &Regex, idx: usize) -> PolarsExpr {
+    let sep = sep.clone();
+    let function = move |s: Series| {
+        let values = s
+            .str()?
+            .into_iter()
+            .map(|value| value.and_then(|value| sep.split(value).nth(idx)))
+            .collect::<StringChunked>();
+        Ok(Some(values.with_name(s.name()).into_series()))
+    };
+
+    col(column)
+        .cast(DataType::String)
+        .map(function, GetOutput::from_type(DataType::String))
+}
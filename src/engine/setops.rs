@@ -0,0 +1,149 @@
+// Copyright (C) 2023 Vince Vasta
+// SPDX-License-Identifier: Apache-2.0
+use polars::lazy::dsl::Expr as PolarsExpr;
+use polars::prelude::*;
+
+use crate::error::{bail, Result};
+use crate::parser::Expr;
+
+use super::*;
+
+/// Evaluates a union or union_all call.
+pub fn eval_union(args: &[Expr], ctx: &mut Context, distinct: bool) -> Result<()> {
+    let name = if distinct { "union" } else { "union_all" };
+    let (lhs_df, rhs_df, _) = operands(name, args, ctx)?;
+
+    let df = concat([lhs_df, rhs_df], Default::default())?;
+    let df = if distinct {
+        df.unique_stable(None, UniqueKeepStrategy::First)
+    } else {
+        df
+    };
+
+    ctx.set_df(df)
+}
+
+/// Evaluates an intersect call, returns the unique rows that are in both
+/// dataframes.
+pub fn eval_intersect(args: &[Expr], ctx: &mut Context) -> Result<()> {
+    let (lhs_df, rhs_df, columns) = operands("intersect", args, ctx)?;
+    ctx.set_df(join_rows(lhs_df, rhs_df, &columns, JoinType::Semi))
+}
+
+/// Evaluates a setdiff call, returns the unique rows in the input dataframe
+/// that are not in the other dataframe.
+pub fn eval_setdiff(args: &[Expr], ctx: &mut Context) -> Result<()> {
+    let (lhs_df, rhs_df, columns) = operands("setdiff", args, ctx)?;
+    ctx.set_df(join_rows(lhs_df, rhs_df, &columns, JoinType::Anti))
+}
+
+/// Joins two dataframes on all columns, nulls are considered equal.
+fn join_rows(
+    lhs_df: LazyFrame,
+    rhs_df: LazyFrame,
+    columns: &[PolarsExpr],
+    join_type: JoinType,
+) -> LazyFrame {
+    let mut join_args = JoinArgs::new(join_type);
+    join_args.join_nulls = true;
+
+    lhs_df
+        .unique_stable(None, UniqueKeepStrategy::First)
+        .join(rhs_df, columns, columns, join_args)
+}
+
+/// Returns the input and the other dataframe with the same columns order and
+/// types, and the columns to compare.
+fn operands(
+    name: &str,
+    args: &[Expr],
+    ctx: &mut Context,
+) -> Result<(LazyFrame, LazyFrame, Vec<PolarsExpr>)> {
+    let Some(lhs_df) = ctx.take_df() else {
+        if ctx.is_grouping() {
+            bail!(
+                Type,
+                "{name} error: must call summarize or ungroup after a group_by"
+            );
+        } else {
+            bail!(Type, "{name} error: missing input dataframe");
+        }
+    };
+
+    let rhs_name = args::identifier(&args[0])?;
+    let Some(rhs_df) = ctx.get_df(&rhs_name).cloned() else {
+        let hint = fuzzy::did_you_mean(&rhs_name, ctx.vars.keys().map(|v| v.as_str()));
+        bail!(
+            Schema,
+            "{name} error: undefined dataframe variable '{rhs_name}'{hint}"
+        );
+    };
+
+    let lhs_schema = lhs_df
+        .schema()
+        .map_err(|e| Error::from_schema(e).context(&format!("{name} error")))?;
+    let rhs_schema = rhs_df
+        .schema()
+        .map_err(|e| Error::from_schema(e).context(&format!("{name} error")))?;
+
+    let missing = |schema: &Schema, other: &Schema| {
+        schema
+            .iter_names()
+            .filter(|c| !other.contains(c))
+            .map(|c| format!("'{c}'"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    let lhs_only = missing(&lhs_schema, &rhs_schema);
+    let rhs_only = missing(&rhs_schema, &lhs_schema);
+    let mut errors = Vec::new();
+    if !lhs_only.is_empty() {
+        errors.push(format!("missing columns in '{rhs_name}': {lhs_only}"));
+    }
+    if !rhs_only.is_empty() {
+        errors.push(format!("missing columns in the input: {rhs_only}"));
+    }
+
+    if !errors.is_empty() {
+        bail!(Schema, "{name} error: {}", errors.join("; "));
+    }
+
+    let mut lhs_columns = Vec::with_capacity(lhs_schema.len());
+    let mut rhs_columns = Vec::with_capacity(lhs_schema.len());
+    let mut columns = Vec::with_capacity(lhs_schema.len());
+
+    for (column, lhs_dtype) in lhs_schema.iter() {
+        let rhs_dtype = rhs_schema.try_get(column)?;
+        if !bind::compatible_types(lhs_dtype, rhs_dtype) {
+            bail!(
+                Schema,
+                "{name} error: column '{column}' has incompatible types {lhs_dtype} and {rhs_dtype}"
+            );
+        }
+
+        let dtype = common_type(lhs_dtype, rhs_dtype);
+        lhs_columns.push(col(column).cast(dtype.clone()));
+        rhs_columns.push(col(column).cast(dtype));
+        columns.push(col(column));
+    }
+
+    Ok((
+        lhs_df.select(lhs_columns),
+        rhs_df.select(rhs_columns),
+        columns,
+    ))
+}
+
+/// Returns the type used to compare two compatible columns.
+fn common_type(lhs: &DataType, rhs: &DataType) -> DataType {
+    if lhs == rhs || rhs.is_null() {
+        lhs.clone()
+    } else if lhs.is_null() {
+        rhs.clone()
+    } else if lhs.is_float() || rhs.is_float() {
+        DataType::Float64
+    } else {
+        DataType::Int64
+    }
+}
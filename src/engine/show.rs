@@ -1,7 +1,6 @@
 // Copyright (C) 2023 Vince Vasta
 // SPDX-License-Identifier: Apache-2.0
-use anyhow::{bail, Result};
-
+use crate::error::{bail, Result};
 use crate::parser::Expr;
 
 use super::*;
@@ -10,13 +9,10 @@ use super::*;
 ///
 /// Parameters are checked before evaluation by the typing module.
 pub fn eval(_args: &[Expr], ctx: &mut Context) -> Result<()> {
-    if let Some(df) = ctx.take_df() {
-        let df = df.collect()?;
+    if let Some((df, _)) = ctx.take_df_or_group() {
         ctx.print(df)?;
-    } else if ctx.is_grouping() {
-        bail!("show error: must call summarize after a group_by");
     } else {
-        bail!("show error: missing input dataframe");
+        bail!(Type, "show error: missing input dataframe");
     }
 
     Ok(())
@@ -0,0 +1,210 @@
+// Copyright (C) 2023 Vince Vasta
+// SPDX-License-Identifier: Apache-2.0
+use polars::lazy::dsl::Expr as PolarsExpr;
+use polars::prelude::*;
+
+use crate::error::{bail, Result};
+use crate::parser::{Expr, Operator};
+
+use super::*;
+
+/// Temporary column with the position of the first row of each group.
+const GROUP_ORDER: &str = "__dply_group_order";
+
+/// Evaluates a tail call.
+///
+/// Parameters are checked before evaluation by the typing module.
+pub fn eval_tail(args: &[Expr], ctx: &mut Context) -> Result<()> {
+    let n = match args.first() {
+        Some(arg) => count("tail", arg)?,
+        None => 10,
+    };
+
+    eval_rows("tail", ctx, |df, keys| {
+        let position = position();
+        let len = len().cast(DataType::Int64);
+        Ok(df.filter(over(position.gt_eq(len - lit(n)), keys)))
+    })
+}
+
+/// Evaluates a slice call, the start position is one based.
+///
+/// Parameters are checked before evaluation by the typing module.
+pub fn eval_slice(args: &[Expr], ctx: &mut Context) -> Result<()> {
+    let start = args::number(&args[0])?;
+    if start < 1.0 {
+        bail!(Type, "slice error: start must be >= 1");
+    }
+
+    let start = start as i64 - 1;
+    let length = count("slice", &args[1])?;
+
+    eval_rows("slice", ctx, |df, keys| {
+        let predicate = position()
+            .gt_eq(lit(start))
+            .and(position().lt(lit(start + length)));
+        Ok(df.filter(over(predicate, keys)))
+    })
+}
+
+/// Evaluates a slice_min or slice_max call.
+///
+/// Rows are sorted by the column values and ties are kept, groups keep the
+/// order of their first row.
+pub fn eval_slice_ordered(args: &[Expr], ctx: &mut Context, descending: bool) -> Result<()> {
+    let name = if descending { "slice_max" } else { "slice_min" };
+    let column = args::identifier(&args[0])?;
+
+    let mut n = 1;
+    for arg in &args[1..] {
+        match arg {
+            Expr::BinaryOp(lhs, Operator::Assign, rhs) => match args::identifier(lhs)?.as_str() {
+                "n" => n = count(name, rhs)?,
+                arg => bail!(Type, "{name} error: unknown argument '{arg}'"),
+            },
+            arg => n = count(name, arg)?,
+        }
+    }
+
+    eval_rows(name, ctx, |df, keys| {
+        let schema = df.schema().map_err(Error::from_schema)?;
+        types::column(&column, &schema).map_err(|e| e.context(&format!("{name} error")))?;
+
+        let options = RankOptions {
+            method: RankMethod::Min,
+            descending,
+        };
+
+        let rank = col(&column).rank(options, None).cast(DataType::Int64);
+        let predicate = over(rank.lt_eq(lit(n)), keys);
+        let sort_options = SortMultipleOptions::default()
+            .with_nulls_last(true)
+            .with_maintain_order(true);
+
+        if keys.is_empty() {
+            let sort_options = sort_options.with_order_descending(descending);
+            return Ok(df.filter(predicate).sort([&column], sort_options));
+        }
+
+        // Sort by column within each group and keep the groups in input order.
+        let group_order = over(col(GROUP_ORDER).min(), keys);
+        let sort_options = sort_options.with_order_descendings([false, descending]);
+        Ok(df
+            .with_row_index(GROUP_ORDER, None)
+            .with_column(group_order)
+            .filter(predicate)
+            .sort([GROUP_ORDER, column.as_str()], sort_options)
+            .drop([GROUP_ORDER]))
+    })
+}
+
+/// Evaluates a slice_sample call.
+///
+/// Parameters are checked before evaluation by the typing module.
+pub fn eval_slice_sample(args: &[Expr], ctx: &mut Context) -> Result<()> {
+    let mut size = None;
+    let mut prop = None;
+    let mut seed = None;
+    let mut replace = false;
+
+    for arg in args {
+        match arg {
+            arg if arg.number().is_some() => size = Some(count("slice_sample", arg)?),
+            Expr::BinaryOp(lhs, Operator::Assign, rhs) => match args::identifier(lhs)?.as_str() {
+                "n" => size = Some(count("slice_sample", rhs)?),
+                "prop" => {
+                    let value = args::number(rhs)?;
+                    if value < 0.0 {
+                        bail!(Type, "slice_sample error: prop must be >= 0");
+                    }
+                    prop = Some(value);
+                }
+                "seed" => seed = Some(count("slice_sample", rhs)? as u64),
+                "replace" => {
+                    replace =
+                        args::bool("replace", rhs).map_err(|e| e.context("slice_sample error"))?
+                }
+                name => bail!(Type, "slice_sample error: unknown argument '{name}'"),
+            },
+            _ => bail!(Type, "slice_sample error: invalid argument '{arg}'"),
+        }
+    }
+
+    if size.is_some() && prop.is_some() {
+        bail!(Type, "slice_sample error: use either n or prop");
+    }
+
+    eval_rows("slice_sample", ctx, |df, keys| {
+        if !replace {
+            // Keeps the rows with the smallest shuffled positions, groups
+            // smaller than the sample size are kept whole.
+            let limit = match prop {
+                Some(prop) => (len().cast(DataType::Float64) * lit(prop)).floor(),
+                None => lit(size.unwrap_or(1)),
+            };
+
+            let predicate = position().shuffle(seed).lt(limit);
+            return Ok(df.filter(over(predicate, keys)));
+        }
+
+        let sample = match prop {
+            Some(prop) => all().sample_frac(lit(prop), true, true, seed),
+            None => all().sample_n(lit(size.unwrap_or(1) as u64), true, true, seed),
+        };
+
+        if keys.is_empty() {
+            Ok(df.select([sample]))
+        } else {
+            // Aggregated group columns are lists of sampled values.
+            let partition = keys.iter().map(|k| col(k)).collect::<Vec<_>>();
+            Ok(df
+                .group_by_stable(partition)
+                .agg([sample])
+                .explode([all().exclude(keys)]))
+        }
+    })
+}
+
+/// Applies a rows transformation to the input dataframe or to each group.
+fn eval_rows(
+    name: &str,
+    ctx: &mut Context,
+    eval: impl FnOnce(LazyFrame, &[String]) -> Result<LazyFrame>,
+) -> Result<()> {
+    if let Some(df) = ctx.take_df() {
+        let df = eval(df, &[])?;
+        ctx.set_df(df)?;
+    } else if let Some((df, keys)) = ctx.take_group_df() {
+        let df = eval(df, &keys)?;
+        let partition = keys.iter().map(|k| col(k)).collect::<Vec<_>>();
+        ctx.set_group(df.group_by_stable(&partition), keys)?;
+    } else {
+        bail!(Type, "{name} error: missing input dataframe");
+    }
+
+    Ok(())
+}
+
+/// Returns the zero based position of each row.
+fn position() -> PolarsExpr {
+    int_range(lit(0), len(), 1, DataType::Int64)
+}
+
+/// Evaluates a predicate for each group if there are group columns.
+fn over(predicate: PolarsExpr, keys: &[String]) -> PolarsExpr {
+    if keys.is_empty() {
+        predicate
+    } else {
+        predicate.over(keys.iter().map(|k| col(k)).collect::<Vec<_>>())
+    }
+}
+
+/// Returns a rows count argument.
+fn count(name: &str, expr: &Expr) -> Result<i64> {
+    let n = args::number(expr)?;
+    if n < 0.0 {
+        bail!(Type, "{name} error: number of rows must be >= 0");
+    }
+
+    Ok(n as i64)
+}
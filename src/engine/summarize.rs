@@ -12,11 +12,11 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
-use anyhow::{bail, Result};
 use polars::lazy::dsl::Expr as PolarsExpr;
 use polars::prelude::*;
 use std::collections::HashSet;
 
+use crate::error::{bail, Result};
 use crate::parser::{Expr, Operator};
 
 use super::*;
@@ -29,19 +29,19 @@ pub fn eval(args: &[Expr], ctx: &mut Context) -> Result<()> {
         let columns = group
             .logical_plan
             .compute_schema()
-            .map_err(anyhow::Error::from)
+            .map_err(Error::from_schema)
             .and_then(|schema| eval_args(args, ctx, &schema, true))
-            .map_err(|e| anyhow!("summarize error: {e}"))?;
+            .map_err(|e| e.context("summarize error"))?;
         ctx.set_df(group.agg(&columns))?;
     } else if let Some(df) = ctx.take_df() {
         let columns = df
             .schema()
-            .map_err(anyhow::Error::from)
+            .map_err(Error::from_schema)
             .and_then(|schema| eval_args(args, ctx, &schema, false))
-            .map_err(|e| anyhow!("summarize error: {e}"))?;
+            .map_err(|e| e.context("summarize error"))?;
         ctx.set_df(df.select(&columns))?;
     } else {
-        bail!("summarize error: missing input group or dataframe");
+        bail!(Type, "summarize error: missing input group or dataframe");
     }
 
     Ok(())
@@ -60,51 +60,144 @@ fn eval_args(
     for arg in args {
         match arg {
             Expr::BinaryOp(lhs, Operator::Assign, rhs) => {
-                let alias = args::identifier(lhs);
+                let alias = args::identifier(lhs)?;
                 if aliases.contains(&alias) {
-                    bail!("summarize error: duplicate alias {alias}");
+                    bail!(Type, "summarize error: duplicate alias {alias}");
                 }
 
                 aliases.insert(alias.clone());
 
-                let column = match rhs.as_ref() {
-                    Expr::Function(name, _) if name == "n" => Ok(col(&schema_cols[0]).count()),
-                    Expr::Function(name, args) if name == "list" => args::column(&args[0], schema)
-                        .map(|c| if grouping { c } else { c.implode() }),
-                    Expr::Function(name, args) if name == "max" => {
-                        args::column(&args[0], schema).map(|c| c.max())
-                    }
-                    Expr::Function(name, args) if name == "mean" => {
-                        args::column(&args[0], schema).map(|c| c.mean())
-                    }
-                    Expr::Function(name, args) if name == "median" => {
-                        args::column(&args[0], schema).map(|c| c.median())
-                    }
-                    Expr::Function(name, args) if name == "min" => {
-                        args::column(&args[0], schema).map(|c| c.min())
-                    }
-                    Expr::Function(name, args) if name == "quantile" => {
-                        let quantile = args::number(&args[1]);
-                        args::column(&args[0], schema)
-                            .map(|c| c.quantile(lit(quantile), QuantileInterpolOptions::Linear))
-                    }
-                    Expr::Function(name, args) if name == "sd" => {
-                        args::column(&args[0], schema).map(|c| c.std(1))
-                    }
-                    Expr::Function(name, args) if name == "sum" => {
-                        args::column(&args[0], schema).map(|c| c.sum())
-                    }
-                    Expr::Function(name, args) if name == "var" => {
-                        args::column(&args[0], schema).map(|c| c.var(1))
-                    }
-                    _ => panic!("Unexpected summarize expression {rhs}"),
-                }?;
-
+                let column = eval_agg(rhs, schema, &schema_cols[0], grouping)?;
                 columns.push(column.alias(&alias));
             }
-            _ => panic!("Unexpected summarize expression: {arg}"),
+            _ => bail!(Type, "Unexpected summarize expression: {arg}"),
         }
     }
 
     Ok(columns)
 }
+
+/// Evaluates an aggregate or an arithmetic expression between aggregates.
+fn eval_agg(expr: &Expr, schema: &Schema, first_col: &str, grouping: bool) -> Result<PolarsExpr> {
+    let column = match expr {
+        Expr::BinaryOp(lhs, op, rhs) => {
+            let lhs_expr = eval_agg(lhs, schema, first_col, grouping)?;
+            let rhs_expr = eval_agg(rhs, schema, first_col, grouping)?;
+            types::check_arith(
+                *op,
+                (lhs, &types::dtype(&lhs_expr, schema)?),
+                (rhs, &types::dtype(&rhs_expr, schema)?),
+            )?;
+
+            match op {
+                Operator::Plus => lhs_expr + rhs_expr,
+                Operator::Minus => lhs_expr - rhs_expr,
+                Operator::Multiply => lhs_expr * rhs_expr,
+                Operator::Divide => lhs_expr / rhs_expr,
+                Operator::Mod => lhs_expr % rhs_expr.cast(DataType::UInt64),
+                Operator::Pow => lhs_expr.cast(DataType::Float64).pow(rhs_expr),
+                _ => bail!(Type, "Unexpected operator {op}"),
+            }
+        }
+        Expr::Number(n) => lit(*n),
+        Expr::UnaryOp(Operator::Minus, expr) => {
+            let value = eval_agg(expr, schema, first_col, grouping)?;
+            expression::negate(expr, value, schema)?
+        }
+        Expr::Function(name, _) if name == "n" => col(first_col).count(),
+        Expr::Function(name, args) if name == "n_distinct" => {
+            agg_arg(name, &args[0], schema)?.n_unique()
+        }
+        Expr::Function(name, args) if name == "approx_n_distinct" => {
+            agg_arg(name, &args[0], schema)?.approx_n_unique()
+        }
+        Expr::Function(name, args) if name == "null_count" => {
+            agg_arg(name, &args[0], schema)?.null_count()
+        }
+        Expr::Function(name, args) if name == "list" => {
+            let c = agg_arg(name, &args[0], schema)?;
+            if grouping {
+                c
+            } else {
+                c.implode()
+            }
+        }
+        Expr::Function(name, args) if name == "first" => agg_arg(name, &args[0], schema)?.first(),
+        Expr::Function(name, args) if name == "last" => agg_arg(name, &args[0], schema)?.last(),
+        Expr::Function(name, args) if name == "nth" => {
+            // nth(x, 1) is the first value and nth(x, -1) the last one.
+            let index = args::number(&args[1])? as i64;
+            if index == 0 {
+                bail!(Type, "`nth` index must not be zero");
+            }
+
+            // Grouped slices and tails return wrong values, the value is
+            // taken from the head of each group or of its reverse.
+            let count = index.unsigned_abs() as usize;
+            let c = agg_arg(name, &args[0], schema)?;
+            let c = if index > 0 { c } else { c.reverse() };
+
+            when(len().gt_eq(lit(count as u32)))
+                .then(c.head(Some(count)).last())
+                .otherwise(lit(NULL))
+        }
+        Expr::Function(name, args) if name == "mode" => {
+            // Returns the smallest value when there are ties.
+            agg_arg(name, &args[0], schema)?
+                .mode()
+                .sort(Default::default())
+                .first()
+        }
+        Expr::Function(name, args) if name == "any" => agg_arg(name, &args[0], schema)?.any(true),
+        Expr::Function(name, args) if name == "all" => agg_arg(name, &args[0], schema)?.all(true),
+        Expr::Function(name, args) if name == "str_join" => {
+            let delimiter = args::string(&args[1])?;
+            agg_arg(name, &args[0], schema)?
+                .str()
+                .concat(&delimiter, true)
+        }
+        Expr::Function(name, args) if name == "max" => agg_arg(name, &args[0], schema)?.max(),
+        Expr::Function(name, args) if name == "mean" => agg_arg(name, &args[0], schema)?.mean(),
+        Expr::Function(name, args) if name == "weighted_mean" => {
+            let value = agg_arg(name, &args[0], schema)?.cast(DataType::Float64);
+            let weight = agg_arg(name, &args[1], schema)?.cast(DataType::Float64);
+
+            // Rows with a null value or weight are ignored.
+            let valid = value
+                .clone()
+                .is_not_null()
+                .and(weight.clone().is_not_null());
+            (value * weight.clone()).sum() / weight.filter(valid).sum()
+        }
+        Expr::Function(name, args) if name == "median" => agg_arg(name, &args[0], schema)?.median(),
+        Expr::Function(name, args) if name == "min" => agg_arg(name, &args[0], schema)?.min(),
+        Expr::Function(name, args) if name == "quantile" => {
+            let quantile = args::number(&args[1])?;
+            agg_arg(name, &args[0], schema)?
+                .quantile(lit(quantile), QuantileInterpolOptions::Linear)
+        }
+        Expr::Function(name, args) if name == "sd" => agg_arg(name, &args[0], schema)?.std(1),
+        Expr::Function(name, args) if name == "sum" => agg_arg(name, &args[0], schema)?.sum(),
+        Expr::Function(name, args) if name == "var" => agg_arg(name, &args[0], schema)?.var(1),
+        Expr::Function(name, args) if name == "skew" => agg_arg(name, &args[0], schema)?.skew(true),
+        Expr::Function(name, args) if name == "kurtosis" => {
+            agg_arg(name, &args[0], schema)?.kurtosis(true, true)
+        }
+        Expr::Function(name, args) if name == "cor" => {
+            let x = agg_arg(name, &args[0], schema)?;
+            let y = agg_arg(name, &args[1], schema)?;
+            pearson_corr(x, y, 1)
+        }
+        _ => bail!(Type, "Unexpected summarize expression {expr}"),
+    };
+
+    Ok(column)
+}
+
+/// Evaluates the argument of an aggregate and checks its type.
+fn agg_arg(fname: &str, arg: &Expr, schema: &Schema) -> Result<PolarsExpr> {
+    let expr = expression::eval(arg, schema)?;
+    let dtype = types::dtype(&expr, schema)?;
+    types::check_agg(fname, &arg.to_string(), &dtype)?;
+    Ok(expr)
+}
@@ -0,0 +1,189 @@
+// Copyright (C) 2023 Vince Vasta
+// SPDX-License-Identifier: Apache-2.0
+
+//! Type checks for pipeline expressions.
+//!
+//! Polars reports most type errors only when a plan is collected, these checks
+//! run when the plan is built so that errors are reported before reading data.
+use polars::lazy::dsl::Expr as PolarsExpr;
+use polars::prelude::*;
+
+use crate::error::{bail, err, Error, Result};
+use crate::fuzzy;
+use crate::parser::{Expr, Operator};
+
+/// Returns the type of a Polars expression evaluated on the given schema.
+pub fn dtype(expr: &PolarsExpr, schema: &Schema) -> Result<DataType> {
+    let schema = schema
+        .iter()
+        .map(|(name, dtype)| Field::new(name, known(dtype)))
+        .collect::<Schema>();
+
+    let schema = DataFrame::from(&schema)
+        .lazy()
+        .select([expr.clone()])
+        .schema()
+        .map_err(Error::from_schema)?;
+
+    schema
+        .get_at_index(0)
+        .map(|(_, dtype)| known(dtype))
+        .ok_or_else(|| err!(Engine, "missing type for expression"))
+}
+
+/// Returns the type of a column.
+pub fn column(column: &str, schema: &Schema) -> Result<DataType> {
+    schema.get(column).map(known).ok_or_else(|| {
+        let hint = fuzzy::did_you_mean(column, schema.iter_names().map(|c| c.as_str()));
+        err!(Schema, "Unknown column '{column}'{hint}")
+    })
+}
+
+/// Checks that an arithmetic operation is valid for the operands types.
+pub fn check_arith(
+    op: Operator,
+    (lhs, ltype): (&Expr, &DataType),
+    (rhs, rtype): (&Expr, &DataType),
+) -> Result<()> {
+    use DataType::*;
+
+    let valid = match (ltype, rtype) {
+        _ if is_unknown(ltype) || is_unknown(rtype) => true,
+        _ if is_number(ltype) && is_number(rtype) => true,
+        _ if matches!(op, Operator::Pow) => false,
+        (String, String) => matches!(op, Operator::Plus),
+        (Datetime(_, _) | Date, Datetime(_, _) | Date) => matches!(op, Operator::Minus),
+        (Datetime(_, _) | Date, Duration(_)) => matches!(op, Operator::Plus | Operator::Minus),
+        (Duration(_), Datetime(_, _) | Date) => matches!(op, Operator::Plus),
+        (Duration(_), Duration(_)) => matches!(op, Operator::Plus | Operator::Minus),
+        (Duration(_), _) if is_number(rtype) => {
+            matches!(op, Operator::Multiply | Operator::Divide)
+        }
+        (_, Duration(_)) if is_number(ltype) => matches!(op, Operator::Multiply),
+        _ => false,
+    };
+
+    if !valid {
+        bail!(
+            Type,
+            "cannot apply '{op}' to '{lhs}' ({ltype}) and '{rhs}' ({rtype})"
+        );
+    }
+
+    Ok(())
+}
+
+/// Checks that a unary minus is valid for the operand type.
+pub fn check_negate(expr: &Expr, dtype: &DataType) -> Result<()> {
+    let valid = is_unknown(dtype) || is_number(dtype) || matches!(dtype, DataType::Duration(_));
+    if !valid {
+        bail!(Type, "cannot apply '-' to '{expr}' ({dtype})");
+    }
+
+    Ok(())
+}
+
+/// Checks that two expressions types can be compared.
+pub fn check_compare(
+    (lhs, ltype): (&Expr, &DataType),
+    (rhs, rtype): (&Expr, &DataType),
+) -> Result<()> {
+    use DataType::*;
+
+    let valid = match (ltype, rtype) {
+        _ if is_unknown(ltype) || is_unknown(rtype) => true,
+        _ if ltype.is_numeric() && rtype.is_numeric() => true,
+        _ if is_text(ltype) && is_text(rtype) => true,
+        (Boolean, Boolean) => true,
+        (Datetime(_, _) | Date, Datetime(_, _) | Date) => true,
+        (Duration(_), Duration(_)) => true,
+        (Time, Time) => true,
+        _ => ltype == rtype && !ltype.is_nested(),
+    };
+
+    if !valid {
+        bail!(
+            Type,
+            "cannot compare '{lhs}' ({ltype}) with '{rhs}' ({rtype})"
+        );
+    }
+
+    Ok(())
+}
+
+/// Checks that an aggregation function supports a column type.
+pub fn check_agg(fname: &str, column: &str, dtype: &DataType) -> Result<()> {
+    use DataType::*;
+
+    let valid = is_unknown(dtype)
+        || match fname {
+            "list" | "n" | "n_distinct" | "approx_n_distinct" | "null_count" => true,
+            "first" | "last" | "nth" => true,
+            "max" | "min" | "mode" => !dtype.is_nested() && !matches!(dtype, Binary),
+            "any" | "all" => dtype.is_bool(),
+            "str_join" => dtype.is_string(),
+            "mean" | "median" => is_number(dtype) || dtype.is_temporal(),
+            "sum" => is_number(dtype) || matches!(dtype, Duration(_)),
+            _ => dtype.is_numeric() || dtype.is_bool(),
+        };
+
+    if !valid {
+        bail!(
+            Type,
+            "cannot compute `{fname}` of column '{column}' with type {dtype}"
+        );
+    }
+
+    Ok(())
+}
+
+/// Checks that a function argument column has the expected type.
+pub fn check_arg(
+    fname: &str,
+    column: &str,
+    dtype: &DataType,
+    expected: &str,
+    is_valid: impl Fn(&DataType) -> bool,
+) -> Result<()> {
+    if !is_unknown(dtype) && !is_valid(dtype) {
+        bail!(
+            Type,
+            "`{fname}` column '{column}' must be {expected}, found {dtype}"
+        );
+    }
+
+    Ok(())
+}
+
+/// Returns true for types that support arithmetic operations.
+fn is_number(dtype: &DataType) -> bool {
+    dtype.is_numeric() || dtype.is_bool()
+}
+
+/// Returns true for string like types.
+fn is_text(dtype: &DataType) -> bool {
+    matches!(dtype, DataType::String | DataType::Categorical(_, _))
+}
+
+/// Replaces the dynamic types of literals with their materialized types.
+fn known(dtype: &DataType) -> DataType {
+    match dtype {
+        DataType::Unknown(UnknownKind::Int(_)) => DataType::Int64,
+        DataType::Unknown(UnknownKind::Float) => DataType::Float64,
+        DataType::Unknown(UnknownKind::Str) => DataType::String,
+        DataType::Unknown(UnknownKind::Any) => DataType::Null,
+        DataType::List(inner) => DataType::List(Box::new(known(inner))),
+        DataType::Struct(fields) => DataType::Struct(
+            fields
+                .iter()
+                .map(|f| Field::new(f.name(), known(f.data_type())))
+                .collect(),
+        ),
+        _ => dtype.clone(),
+    }
+}
+
+/// Returns true for types that cannot be checked statically.
+fn is_unknown(dtype: &DataType) -> bool {
+    matches!(dtype, DataType::Null | DataType::Unknown(_))
+}
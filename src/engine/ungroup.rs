@@ -0,0 +1,23 @@
+// Copyright (C) 2023 Vince Vasta
+// SPDX-License-Identifier: Apache-2.0
+use polars::prelude::*;
+
+use crate::error::{bail, Result};
+use crate::parser::Expr;
+
+use super::*;
+
+/// Evaluates an ungroup call.
+///
+/// Parameters are checked before evaluation by the typing module.
+pub fn eval(_args: &[Expr], ctx: &mut Context) -> Result<()> {
+    if let Some(group) = ctx.take_group() {
+        ctx.set_df(LazyFrame::from(group))?;
+    } else if let Some(df) = ctx.take_df() {
+        ctx.set_df(df)?;
+    } else {
+        bail!(Type, "ungroup error: missing input group or dataframe");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,80 @@
+// Copyright (C) 2023 Vince Vasta
+// SPDX-License-Identifier: Apache-2.0
+use polars::prelude::*;
+
+use crate::error::{bail, Result};
+use crate::parser::{Expr, Operator};
+
+use super::*;
+
+/// Evaluates a unite call.
+///
+/// Parameters are checked before evaluation by the typing module.
+pub fn eval(args: &[Expr], ctx: &mut Context) -> Result<()> {
+    if let Some(df) = ctx.take_df() {
+        let schema_cols = ctx.columns();
+        let name = args::identifier(&args[0])?;
+        let mut sep = "_".to_string();
+        let mut remove = true;
+        let mut unite_columns = Vec::new();
+
+        for arg in &args[1..] {
+            match arg {
+                Expr::BinaryOp(lhs, Operator::Assign, rhs) => {
+                    match args::identifier(lhs)?.as_str() {
+                        "sep" => sep = args::string(rhs)?,
+                        "remove" => {
+                            remove =
+                                args::bool("remove", rhs).map_err(|e| e.context("unite error"))?
+                        }
+                        _ => {}
+                    }
+                }
+                Expr::Identifier(column) => {
+                    if !schema_cols.contains(column) {
+                        let hint =
+                            fuzzy::did_you_mean(column, schema_cols.iter().map(|c| c.as_str()));
+                        bail!(Schema, "unite error: Unknown column '{column}'{hint}");
+                    }
+
+                    if !unite_columns.contains(column) {
+                        unite_columns.push(column.to_owned());
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if unite_columns.is_empty() {
+            bail!(Type, "unite error: missing columns to unite");
+        }
+
+        let exprs = unite_columns.iter().map(|c| col(c)).collect::<Vec<_>>();
+        let united = concat_str(exprs, &sep, false).alias(&name);
+
+        // The new column takes the position of the leftmost united column.
+        let first = schema_cols.iter().find(|c| unite_columns.contains(c));
+        let mut columns = Vec::with_capacity(schema_cols.len() + 1);
+        for c in schema_cols {
+            if Some(c) == first {
+                columns.push(united.clone());
+                if !remove && *c != name {
+                    columns.push(col(c));
+                }
+            } else if *c != name && !(remove && unite_columns.contains(c)) {
+                columns.push(col(c));
+            }
+        }
+
+        ctx.set_df(df.select(&columns))?;
+    } else if ctx.is_grouping() {
+        bail!(
+            Type,
+            "unite error: must call summarize or ungroup after a group_by"
+        );
+    } else {
+        bail!(Type, "unite error: missing input dataframe");
+    }
+
+    Ok(())
+}
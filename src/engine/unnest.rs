@@ -12,9 +12,9 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
-use anyhow::{bail, Result};
 use polars::prelude::*;
 
+use crate::error::{bail, Result};
 use crate::parser::Expr;
 
 use super::*;
@@ -25,8 +25,10 @@ use super::*;
 pub fn eval(args: &[Expr], ctx: &mut Context) -> Result<()> {
     if let Some(mut df) = ctx.take_df() {
         for arg in args {
-            let column = args::identifier(arg);
-            let schema = df.schema().map_err(|e| anyhow!("unnest error: {e}"))?;
+            let column = args::identifier(arg)?;
+            let schema = df
+                .schema()
+                .map_err(|e| Error::from_schema(e).context("unnest error"))?;
 
             match schema.get(&column) {
                 Some(DataType::List(_)) => {
@@ -35,14 +37,21 @@ pub fn eval(args: &[Expr], ctx: &mut Context) -> Result<()> {
                 Some(DataType::Struct(_)) => {
                     df = df.unnest([&column]);
                 }
-                Some(_) => bail!("unnest error: '{column}' is not a list or struct type"),
-                None => bail!("unnest error: unknown column '{column}'"),
+                Some(_) => bail!(
+                    Type,
+                    "unnest error: '{column}' is not a list or struct type"
+                ),
+                None => {
+                    let hint =
+                        fuzzy::did_you_mean(&column, schema.iter_names().map(|c| c.as_str()));
+                    bail!(Schema, "unnest error: unknown column '{column}'{hint}");
+                }
             }
         }
 
         ctx.set_df(df)?;
     } else {
-        bail!("unnest error: missing input dataframe");
+        bail!(Type, "unnest error: missing input dataframe");
     }
 
     Ok(())
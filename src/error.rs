@@ -0,0 +1,178 @@
+// Copyright (C) 2023 Vince Vasta
+// SPDX-License-Identifier: Apache-2.0
+
+//! Errors returned by the dply interpreter.
+use polars::prelude::PolarsError;
+use thiserror::Error;
+
+use crate::parser::Location;
+
+/// A dply error.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// The script has invalid syntax.
+    #[error("Parse error: {0}")]
+    Parse(String),
+    /// A function is unknown, has invalid arguments, or is not valid at its
+    /// position in the pipeline.
+    #[error("{0}")]
+    Type(String),
+    /// A column or variable is not defined.
+    #[error("{0}")]
+    Schema(String),
+    /// A file cannot be read or written.
+    #[error("{0}")]
+    Io(String),
+    /// A pipeline failed during evaluation.
+    #[error("{0}")]
+    Engine(String),
+    /// An error with the location of the expression that caused it.
+    #[error("{error}")]
+    Located {
+        /// The located error.
+        error: Box<Error>,
+        /// The location of the expression in the script.
+        location: Location,
+        /// The pipeline function where the error occurred.
+        function: Option<String>,
+    },
+}
+
+/// A result with a dply error.
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    /// Returns the error kind name, one of parse, type, schema, io, or engine.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Error::Parse(_) => "parse",
+            Error::Type(_) => "type",
+            Error::Schema(_) => "schema",
+            Error::Io(_) => "io",
+            Error::Engine(_) => "engine",
+            Error::Located { error, .. } => error.kind(),
+        }
+    }
+
+    /// Returns the error message without the location.
+    pub fn message(&self) -> &str {
+        match self {
+            Error::Parse(msg)
+            | Error::Type(msg)
+            | Error::Schema(msg)
+            | Error::Io(msg)
+            | Error::Engine(msg) => msg,
+            Error::Located { error, .. } => error.message(),
+        }
+    }
+
+    /// Returns the location of the expression that caused the error.
+    pub fn location(&self) -> Option<Location> {
+        match self {
+            Error::Located { location, .. } => Some(*location),
+            _ => None,
+        }
+    }
+
+    /// Returns the name of the pipeline function that caused the error.
+    pub fn function(&self) -> Option<&str> {
+        match self {
+            Error::Located { function, .. } => function.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Sets the location of the error if it doesn't have one.
+    pub(crate) fn at(self, location: Location, function: Option<&str>) -> Self {
+        match self {
+            Error::Located { .. } => self,
+            error => Error::Located {
+                error: Box::new(error),
+                location,
+                function: function.map(|f| f.to_string()),
+            },
+        }
+    }
+
+    /// Prefixes the error message with a context keeping the error kind.
+    pub(crate) fn context(self, context: &str) -> Self {
+        match self {
+            Error::Parse(msg) => Error::Parse(format!("{context}: {msg}")),
+            Error::Type(msg) => Error::Type(format!("{context}: {msg}")),
+            Error::Schema(msg) => Error::Schema(format!("{context}: {msg}")),
+            Error::Io(msg) => Error::Io(format!("{context}: {msg}")),
+            Error::Engine(msg) => Error::Engine(format!("{context}: {msg}")),
+            Error::Located {
+                error,
+                location,
+                function,
+            } => Error::Located {
+                error: Box::new(error.context(context)),
+                location,
+                function,
+            },
+        }
+    }
+}
+
+impl Error {
+    /// Converts a polars error raised while computing a dataframe schema,
+    /// invalid operations found before reading the data are type errors.
+    pub(crate) fn from_schema(e: PolarsError) -> Self {
+        match innermost(&e) {
+            PolarsError::InvalidOperation(_) | PolarsError::SchemaMismatch(_) => {
+                Error::Type(e.to_string())
+            }
+            _ => Error::from(e),
+        }
+    }
+}
+
+impl From<PolarsError> for Error {
+    fn from(e: PolarsError) -> Self {
+        match innermost(&e) {
+            PolarsError::IO { .. } => Error::Io(e.to_string()),
+            PolarsError::ColumnNotFound(_)
+            | PolarsError::SchemaFieldNotFound(_)
+            | PolarsError::StructFieldNotFound(_) => Error::Schema(e.to_string()),
+            _ => Error::Engine(e.to_string()),
+        }
+    }
+}
+
+/// Returns the innermost error, polars wraps errors with the operation that
+/// failed, for example a scan of a missing file.
+fn innermost(e: &PolarsError) -> &PolarsError {
+    let mut inner = e;
+    while let PolarsError::Context { error, .. } = inner {
+        inner = error;
+    }
+    inner
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(e.to_string())
+    }
+}
+
+/// Returns early with an error of the given kind.
+///
+/// ```ignore
+/// bail!(Schema, "Unknown column '{column}'");
+/// ```
+macro_rules! bail {
+    ($kind:ident, $($arg:tt)*) => {
+        return Err($crate::error::err!($kind, $($arg)*))
+    };
+}
+
+/// Creates an error of the given kind.
+macro_rules! err {
+    ($kind:ident, $($arg:tt)*) => {
+        $crate::error::Error::$kind(format!($($arg)*))
+    };
+}
+
+pub(crate) use bail;
+pub(crate) use err;
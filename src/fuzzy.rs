@@ -32,6 +32,67 @@ impl Matcher {
     }
 }
 
+/// Returns the candidate closest to the given name if it is a likely typo.
+///
+/// Candidates within a small edit distance are preferred, otherwise candidates
+/// that fuzzy match the name are considered.
+pub fn closest<'a, I>(name: &str, candidates: I) -> Option<&'a str>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let max_distance = (name.chars().count() / 3).max(1);
+    let matcher = Matcher::new(name);
+
+    candidates
+        .into_iter()
+        .filter(|c| *c != name)
+        .map(|c| (distance(name, c), c))
+        .filter(|(d, c)| *d <= max_distance || (name.len() > 2 && matcher.is_match(c)))
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, c)| c)
+}
+
+/// Returns a hint with the closest candidate, or an empty string if there is
+/// no close match.
+pub fn did_you_mean<'a, I>(name: &str, candidates: I) -> String
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    closest(name, candidates)
+        .map(|c| format!(", did you mean '{c}'?"))
+        .unwrap_or_default()
+}
+
+/// Case insensitive edit distance where adjacent transpositions count as one edit.
+fn distance(lhs: &str, rhs: &str) -> usize {
+    let lhs = lhs.to_lowercase().chars().collect::<Vec<_>>();
+    let rhs = rhs.to_lowercase().chars().collect::<Vec<_>>();
+
+    // d[i][j] is the distance between the first i chars of lhs and j chars of rhs.
+    let mut d = vec![vec![0; rhs.len() + 1]; lhs.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=lhs.len() {
+        for j in 1..=rhs.len() {
+            let cost = usize::from(lhs[i - 1] != rhs[j - 1]);
+            d[i][j] = (d[i - 1][j - 1] + cost)
+                .min(d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1);
+
+            if i > 1 && j > 1 && lhs[i - 1] == rhs[j - 2] && lhs[i - 2] == rhs[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+
+    d[lhs.len()][rhs.len()]
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -52,4 +113,27 @@ mod tests {
         assert!(!Matcher::new("eee").is_match("select"));
         assert!(!Matcher::new("stt").is_match("select"));
     }
+
+    #[test]
+    fn closest_match() {
+        let names = ["filter", "mutate", "select", "amount", "overwrite"];
+
+        assert_eq!(closest("fitler", names), Some("filter"));
+        assert_eq!(closest("mtuate", names), Some("mutate"));
+        assert_eq!(closest("amout", names), Some("amount"));
+        assert_eq!(closest("overwite", names), Some("overwrite"));
+        assert_eq!(closest("SELECT", names), Some("select"));
+        assert_eq!(closest("mut", names), Some("mutate"));
+        assert_eq!(closest("summarize", names), None);
+        assert_eq!(closest("filter", names), None);
+    }
+
+    #[test]
+    fn edit_distance() {
+        assert_eq!(distance("", ""), 0);
+        assert_eq!(distance("abc", ""), 3);
+        assert_eq!(distance("kitten", "sitting"), 3);
+        assert_eq!(distance("Filter", "filter"), 0);
+        assert_eq!(distance("fitler", "filter"), 1);
+    }
 }
@@ -2,8 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 //! Interpreter for dply expressions.
-use anyhow::Result;
-
+use crate::error::Result;
 use crate::{engine, parser, typing};
 
 /// Evaluates a dply script.
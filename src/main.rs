@@ -10,12 +10,13 @@ use jemallocator::Jemalloc;
 #[cfg(all(not(debug_assertions), target_family = "unix",))]
 static ALLOC: Jemalloc = Jemalloc;
 
-use anyhow::{anyhow, Result};
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use std::fs;
 use std::io::{self, IsTerminal, Read};
 use std::path::PathBuf;
+use std::process::ExitCode;
 
+use dply::error::{Error, Result};
 use dply::{interpreter, repl};
 
 /// Cli interface.
@@ -28,24 +29,94 @@ pub struct Cli {
     /// dply command passed as string.
     #[arg(long, short)]
     pub command: Option<String>,
+
+    /// Format of errors written to standard error.
+    #[arg(long, value_enum, default_value_t = ErrorFormat::Text)]
+    pub error_format: ErrorFormat,
+}
+
+/// Errors output format.
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ErrorFormat {
+    /// Human readable errors.
+    Text,
+    /// One JSON object per error with kind, message, and location.
+    Json,
 }
 
-fn main() -> Result<()> {
+/// Exit code for errors not caused by a script, like a terminal failure.
+const EXIT_FAILURE: u8 = 1;
+/// Exit code for scripts with invalid syntax.
+const EXIT_PARSE: u8 = 2;
+/// Exit code for unknown functions, columns, or invalid types.
+const EXIT_VALIDATION: u8 = 3;
+/// Exit code for files that cannot be read or written.
+const EXIT_IO: u8 = 4;
+/// Exit code for errors while processing the data.
+const EXIT_RUNTIME: u8 = 5;
+
+fn main() -> ExitCode {
     let cli = Cli::parse();
 
-    if let Some(input) = cli.command {
-        interpreter::eval(&input)?;
-    } else if let Some(path) = cli.path {
-        let input = fs::read_to_string(&path)
-            .map_err(|e| anyhow!("Error reading script {}: {e}", path.display()))?;
-        interpreter::eval(&input)?;
-    } else if io::stdin().is_terminal() {
-        repl::run()?;
+    if cli.command.is_none() && cli.path.is_none() && io::stdin().is_terminal() {
+        return match repl::run() {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("Error: {e}");
+                ExitCode::from(EXIT_FAILURE)
+            }
+        };
+    }
+
+    match run(&cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            report(&cli, &e);
+
+            ExitCode::from(match e.kind() {
+                "parse" => EXIT_PARSE,
+                "type" | "schema" => EXIT_VALIDATION,
+                "io" => EXIT_IO,
+                _ => EXIT_RUNTIME,
+            })
+        }
+    }
+}
+
+fn run(cli: &Cli) -> Result<()> {
+    if let Some(input) = &cli.command {
+        interpreter::eval(input)
+    } else if let Some(path) = &cli.path {
+        let input = fs::read_to_string(path)
+            .map_err(|e| Error::Io(format!("Error reading script {}: {e}", path.display())))?;
+        interpreter::eval(&input)
     } else {
         let mut input = String::new();
         io::stdin().read_to_string(&mut input)?;
-        interpreter::eval(&input)?;
-    };
+        interpreter::eval(&input)
+    }
+}
 
-    Ok(())
+fn report(cli: &Cli, e: &Error) {
+    match cli.error_format {
+        ErrorFormat::Text => match e.location() {
+            Some(location) if e.kind() != "parse" => eprintln!(
+                "Error at line {}, column {}: {e}",
+                location.line, location.column
+            ),
+            _ => eprintln!("Error: {e}"),
+        },
+        ErrorFormat::Json => {
+            let location = e.location();
+            let json = serde_json::json!({
+                "kind": e.kind(),
+                "message": e.message(),
+                "file": cli.path.as_ref().map(|p| p.display().to_string()),
+                "line": location.map(|l| l.line),
+                "column": location.map(|l| l.column),
+                "function": e.function(),
+            });
+            eprintln!("{json}");
+        }
+    }
 }
@@ -2,22 +2,23 @@
 // SPDX-License-Identifier: Apache-2.0
 
 //! Parser for dply expressions.
-use anyhow::{bail, Result};
 use nom::branch::alt;
 use nom::bytes::complete::{is_a, is_not, tag};
 use nom::character::complete::{alpha1, alphanumeric1, char, multispace0, newline};
-use nom::combinator::{cut, map, recognize, value, verify};
-use nom::error::{context, convert_error, VerboseError};
-use nom::multi::{many0, many0_count, many1_count, separated_list0, separated_list1};
+use nom::combinator::{cut, map, not, opt, recognize, value, verify};
+use nom::error::{context, convert_error, VerboseError, VerboseErrorKind};
+use nom::multi::{many0, many0_count, many1, many1_count, separated_list0, separated_list1};
 use nom::number::complete::double;
 use nom::sequence::{delimited, pair, preceded, terminated, tuple};
 use nom::IResult;
 use std::fmt;
 
+use crate::error::{err, Error, Result};
+
 /// A parsed dply expression.
 pub enum Expr {
-    /// A pipeline of data manipulation expressions.
-    Pipeline(Vec<Expr>),
+    /// A pipeline of data manipulation expressions with their locations.
+    Pipeline(Vec<Expr>, Vec<Location>),
     /// A function invocation.
     Function(String, Vec<Expr>),
     /// Binary operation
@@ -30,6 +31,31 @@ pub enum Expr {
     String(String),
     /// A number literal
     Number(f64),
+    /// A list of expressions `(a, b, c)`
+    List(Vec<Expr>),
+}
+
+/// The position of an expression in a script, lines and columns start at 1.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Location {
+    /// Line number.
+    pub line: usize,
+    /// Column number.
+    pub column: usize,
+}
+
+impl Location {
+    /// Returns the location of `pos` that must be a sub slice of `input`.
+    fn new(input: &str, pos: &str) -> Self {
+        let offset = (pos.as_ptr() as usize).saturating_sub(input.as_ptr() as usize);
+        let prefix = &input[..offset.min(input.len())];
+        let line_start = prefix.rfind('\n').map(|p| p + 1).unwrap_or(0);
+
+        Self {
+            line: prefix.matches('\n').count() + 1,
+            column: prefix[line_start..].chars().count() + 1,
+        }
+    }
 }
 
 /// A binary operation.
@@ -47,6 +73,8 @@ pub enum Operator {
     Gt,
     /// Left side is greater or equal to right side
     GtEq,
+    /// Left side is one of the right side values
+    In,
     /// Addition
     Plus,
     /// Subtraction
@@ -57,6 +85,8 @@ pub enum Operator {
     Divide,
     /// Mod
     Mod,
+    /// Power
+    Pow,
     /// Logical and
     And,
     /// Logical or
@@ -65,6 +95,8 @@ pub enum Operator {
     Not,
     /// Assignment
     Assign,
+    /// Condition and value in a `case_when` branch
+    Formula,
 }
 
 impl fmt::Display for Operator {
@@ -76,25 +108,50 @@ impl fmt::Display for Operator {
             Operator::LtEq => "<=",
             Operator::Gt => ">",
             Operator::GtEq => ">=",
+            Operator::In => "%in%",
             Operator::Plus => "+",
             Operator::Minus => "-",
             Operator::Multiply => "*",
             Operator::Divide => "/",
             Operator::Mod => "%",
+            Operator::Pow => "^",
             Operator::And => "&",
             Operator::Or => "|",
             Operator::Not => "!",
             Operator::Assign => "=",
+            Operator::Formula => "~",
         };
 
         write!(f, "{op}")
     }
 }
 
+impl Expr {
+    /// Returns the function name for a function expression.
+    pub fn function_name(&self) -> Option<&str> {
+        match self {
+            Expr::Function(name, _) => Some(name),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of a number literal, including a negated one like `-2`.
+    pub fn number(&self) -> Option<f64> {
+        match self {
+            Expr::Number(n) => Some(*n),
+            Expr::UnaryOp(Operator::Minus, expr) => match expr.as_ref() {
+                Expr::Number(n) => Some(-n),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
 impl fmt::Display for Expr {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Expr::Pipeline(exprs) => {
+            Expr::Pipeline(exprs, _) => {
                 for (idx, expr) in exprs.iter().enumerate() {
                     if idx > 0 {
                         write!(f, " | ")?;
@@ -118,13 +175,23 @@ impl fmt::Display for Expr {
                 write!(f, " {op} ")?;
                 rhs.fmt(f)
             }
-            Expr::UnaryOp(op, expr) => {
-                write!(f, "{op}")?;
-                expr.fmt(f)
-            }
+            Expr::UnaryOp(op, expr) => match expr.as_ref() {
+                Expr::BinaryOp(_, _, _) => write!(f, "{op}({expr})"),
+                _ => write!(f, "{op}{expr}"),
+            },
             Expr::Identifier(n) => write!(f, "{n}"),
             Expr::String(s) => write!(f, r#""{s}""#),
             Expr::Number(n) => write!(f, "{n}"),
+            Expr::List(exprs) => {
+                write!(f, "(")?;
+                for (idx, expr) in exprs.iter().enumerate() {
+                    if idx > 0 {
+                        write!(f, ", ")?;
+                    }
+                    expr.fmt(f)?;
+                }
+                write!(f, ")")
+            }
         }
     }
 }
@@ -143,7 +210,7 @@ fn fmt_debug(expr: &Expr, indent: usize, f: &mut fmt::Formatter<'_>) -> fmt::Res
     }
 
     match expr {
-        Expr::Pipeline(exprs) => {
+        Expr::Pipeline(exprs, _) => {
             windent!(f, "pre_pipeline")?;
             for expr in exprs {
                 fmt_debug(expr, indent + 2, f)?;
@@ -171,6 +238,13 @@ fn fmt_debug(expr: &Expr, indent: usize, f: &mut fmt::Formatter<'_>) -> fmt::Res
         Expr::Identifier(id) => windent!(f, "identifier: {id}"),
         Expr::String(s) => windent!(f, "string: {s}"),
         Expr::Number(n) => windent!(f, "number: {n}"),
+        Expr::List(exprs) => {
+            windent!(f, "pre_list({})", exprs.len())?;
+            for expr in exprs {
+                fmt_debug(expr, indent + 2, f)?;
+            }
+            windent!(f, "post_list({})", exprs.len())
+        }
     }
 }
 
@@ -189,8 +263,12 @@ fn name(input: &str) -> IResult<&str, &str, VerboseError<&str>> {
     ))(input)
 }
 
+/// An identifier, `df.col` refers to a column of a dataframe variable.
 fn identifier(input: &str) -> IResult<&str, Expr, VerboseError<&str>> {
-    map(preceded(ws, name), |s| Expr::Identifier(s.to_string()))(input)
+    map(
+        preceded(ws, recognize(pair(name, opt(pair(char('.'), name))))),
+        |s| Expr::Identifier(s.to_string()),
+    )(input)
 }
 
 fn quoted(input: &str) -> IResult<&str, Expr, VerboseError<&str>> {
@@ -202,10 +280,10 @@ fn quoted(input: &str) -> IResult<&str, Expr, VerboseError<&str>> {
 }
 
 fn string(input: &str) -> IResult<&str, Expr, VerboseError<&str>> {
-    let literal = verify(is_not("\""), |s: &str| !s.is_empty());
+    let literal = opt(is_not("\""));
     map(
         preceded(char('"'), cut(terminated(literal, char('"')))),
-        |s: &str| Expr::String(s.to_string()),
+        |s: Option<&str>| Expr::String(s.unwrap_or_default().to_string()),
     )(input)
 }
 
@@ -224,6 +302,33 @@ fn group(input: &str) -> IResult<&str, Expr, VerboseError<&str>> {
     )(input)
 }
 
+/// A list expression `(a, b, c)` with at least two elements, or `[a, b, c]`
+/// with any number of elements.
+fn list(input: &str) -> IResult<&str, Expr, VerboseError<&str>> {
+    fn item(input: &str) -> IResult<&str, Expr, VerboseError<&str>> {
+        preceded(multispace0, alt((arith_op, expression)))(input)
+    }
+
+    let separator = || preceded(multispace0, char(','));
+
+    let tuple = delimited(
+        char('('),
+        map(
+            pair(item, many1(preceded(separator(), item))),
+            |(first, rest)| Expr::List(std::iter::once(first).chain(rest).collect()),
+        ),
+        cut(preceded(multispace0, char(')'))),
+    );
+
+    let brackets = delimited(
+        char('['),
+        map(separated_list0(separator(), item), Expr::List),
+        cut(preceded(multispace0, char(']'))),
+    );
+
+    context("list", preceded(multispace0, alt((tuple, brackets))))(input)
+}
+
 fn expression(input: &str) -> IResult<&str, Expr, VerboseError<&str>> {
     context(
         "expression",
@@ -236,6 +341,7 @@ fn expression(input: &str) -> IResult<&str, Expr, VerboseError<&str>> {
                 identifier,
                 string,
                 map(double, Expr::Number),
+                list,
                 group,
             )),
         ),
@@ -246,12 +352,15 @@ fn unary_op(input: &str) -> IResult<&str, Expr, VerboseError<&str>> {
     let operator = alt((
         map(tag("+"), |_| Operator::Plus),
         map(tag("-"), |_| Operator::Minus),
-        map(tag("!"), |_| Operator::Not),
     ));
 
+    // A sign applies to a power so that `-a ^ b` is `-(a ^ b)`.
+    let sign = pair(operator, power);
+    let not = pair(map(tag("!"), |_| Operator::Not), expression);
+
     context(
         "unary_op",
-        map(pair(operator, expression), |(op, expr)| {
+        map(alt((sign, not)), |(op, expr)| {
             Expr::UnaryOp(op, Box::new(expr))
         }),
     )(input)
@@ -259,6 +368,7 @@ fn unary_op(input: &str) -> IResult<&str, Expr, VerboseError<&str>> {
 
 fn compare_op(input: &str) -> IResult<&str, Expr, VerboseError<&str>> {
     let operator = alt((
+        map(tag("%in%"), |_| Operator::In),
         map(tag("=="), |_| Operator::Eq),
         map(tag("!="), |_| Operator::NotEq),
         map(tag("<="), |_| Operator::LtEq),
@@ -271,9 +381,9 @@ fn compare_op(input: &str) -> IResult<&str, Expr, VerboseError<&str>> {
         "binary_op",
         map(
             tuple((
-                preceded(multispace0, expression),
+                preceded(multispace0, alt((arith_op, expression))),
                 preceded(multispace0, operator),
-                preceded(multispace0, alt((compare_op, expression))),
+                preceded(multispace0, alt((compare_op, arith_op, expression))),
             )),
             |(lhs, op, rhs)| Expr::BinaryOp(Box::new(lhs), op, Box::new(rhs)),
         ),
@@ -299,21 +409,75 @@ fn logical_op(input: &str) -> IResult<&str, Expr, VerboseError<&str>> {
     )(input)
 }
 
+/// An arithmetic expression with at least one operator.
+///
+/// `^` has the highest precedence followed by `*`, `/`, `%`, and then by `+`,
+/// `-`, operators are left associative except for `^`.
 fn arith_op(input: &str) -> IResult<&str, Expr, VerboseError<&str>> {
+    context(
+        "arith_op",
+        verify(sum, |expr| matches!(expr, Expr::BinaryOp(_, _, _))),
+    )(input)
+}
+
+fn sum(input: &str) -> IResult<&str, Expr, VerboseError<&str>> {
     let operator = alt((
         map(tag("+"), |_| Operator::Plus),
         map(tag("-"), |_| Operator::Minus),
+    ));
+
+    map(
+        pair(
+            product,
+            many0(pair(preceded(multispace0, operator), product)),
+        ),
+        fold_binary_ops,
+    )(input)
+}
+
+fn product(input: &str) -> IResult<&str, Expr, VerboseError<&str>> {
+    let operator = alt((
         map(tag("*"), |_| Operator::Multiply),
         map(tag("/"), |_| Operator::Divide),
-        map(tag("%"), |_| Operator::Mod),
+        // `%` is not the start of the `%in%` operator.
+        map(terminated(tag("%"), not(tag("in%"))), |_| Operator::Mod),
     ));
 
+    map(
+        pair(power, many0(pair(preceded(multispace0, operator), power))),
+        fold_binary_ops,
+    )(input)
+}
+
+fn power(input: &str) -> IResult<&str, Expr, VerboseError<&str>> {
+    let operator = map(tag("^"), |_| Operator::Pow);
+
+    map(
+        pair(
+            preceded(multispace0, expression),
+            opt(pair(preceded(multispace0, operator), power)),
+        ),
+        |(lhs, rhs)| match rhs {
+            Some((op, rhs)) => Expr::BinaryOp(Box::new(lhs), op, Box::new(rhs)),
+            None => lhs,
+        },
+    )(input)
+}
+
+/// Folds a sequence of left associative binary operations.
+fn fold_binary_ops((lhs, rest): (Expr, Vec<(Operator, Expr)>)) -> Expr {
+    rest.into_iter().fold(lhs, |lhs, (op, rhs)| {
+        Expr::BinaryOp(Box::new(lhs), op, Box::new(rhs))
+    })
+}
+
+fn assign_op(input: &str) -> IResult<&str, Expr, VerboseError<&str>> {
     context(
         "logical_op",
         map(
             tuple((
-                preceded(multispace0, expression),
-                preceded(multispace0, operator),
+                preceded(multispace0, alt((quoted, identifier))),
+                preceded(multispace0, map(tag("="), |_| Operator::Assign)),
                 preceded(multispace0, alt((arith_op, expression))),
             )),
             |(lhs, op, rhs)| Expr::BinaryOp(Box::new(lhs), op, Box::new(rhs)),
@@ -321,13 +485,14 @@ fn arith_op(input: &str) -> IResult<&str, Expr, VerboseError<&str>> {
     )(input)
 }
 
-fn assign_op(input: &str) -> IResult<&str, Expr, VerboseError<&str>> {
+/// A formula expression `a > b ~ c` that maps a condition to a value.
+fn formula_op(input: &str) -> IResult<&str, Expr, VerboseError<&str>> {
     context(
-        "logical_op",
+        "formula_op",
         map(
             tuple((
-                preceded(multispace0, alt((quoted, identifier))),
-                preceded(multispace0, map(tag("="), |_| Operator::Assign)),
+                preceded(multispace0, alt((logical_op, compare_op, expression))),
+                preceded(multispace0, map(tag("~"), |_| Operator::Formula)),
                 preceded(multispace0, alt((arith_op, expression))),
             )),
             |(lhs, op, rhs)| Expr::BinaryOp(Box::new(lhs), op, Box::new(rhs)),
@@ -341,7 +506,7 @@ fn argument(input: &str) -> IResult<&str, Expr, VerboseError<&str>> {
         preceded(
             multispace0,
             alt((
-                assign_op, logical_op, compare_op, unary_op, arith_op, expression,
+                formula_op, assign_op, logical_op, compare_op, arith_op, expression,
             )),
         ),
     )(input)
@@ -365,41 +530,69 @@ fn function(input: &str) -> IResult<&str, Expr, VerboseError<&str>> {
     )(input)
 }
 
+/// A pipeline step with the input where it starts.
+type Step<'a> = (&'a str, Expr);
+
+/// Parses a pipeline step, a function call or an identifier.
+fn step(input: &str) -> IResult<&str, Step<'_>, VerboseError<&str>> {
+    let (input, _) = ws(input)?;
+    let (rest, expr) = alt((function, identifier))(input)?;
+    Ok((rest, (input, expr)))
+}
+
 /// Parses a pipeline.
 ///
 /// A pipeline can be a list of function calls or identifiers separated by a pipe.
-fn pipeline(input: &str) -> IResult<&str, Expr, VerboseError<&str>> {
+fn pipeline(input: &str) -> IResult<&str, Vec<Step<'_>>, VerboseError<&str>> {
     let separator = tuple((multispace0, tag("|"), multispace0));
-
-    context(
-        "pipeline",
-        map(
-            separated_list0(separator, cut(alt((function, identifier)))),
-            Expr::Pipeline,
-        ),
-    )(input)
+    context("pipeline", separated_list0(separator, cut(step)))(input)
 }
 
 /// Parses one or more pipelines.
-fn root(input: &str) -> IResult<&str, Vec<Expr>, VerboseError<&str>> {
+fn root(input: &str) -> IResult<&str, Vec<Vec<Step<'_>>>, VerboseError<&str>> {
     let separator = alt((char(';'), newline));
     separated_list1(many1_count(separator), cut(pipeline))(input)
 }
 
 /// Parses one or more dply pipelines.
 pub fn parse(input: &str) -> Result<Vec<Expr>> {
+    // Replace comments with empty lines to keep lines numbers for errors.
     let input = input
         .lines()
-        .filter(|line| comment(line).is_err())
+        .map(|line| if comment(line).is_ok() { "" } else { line })
         .map(|line| line.to_string() + "\n")
         .collect::<String>();
 
     match root(input.trim().trim_end_matches(';')) {
-        Err(nom::Err::Error(e)) | Err(nom::Err::Failure(e)) => {
-            bail!("Parse error: {}", convert_error(input.as_str(), e))
-        }
-        Err(e) => bail!("Parse error: {e}"),
-        Ok((_, exprs)) => Ok(exprs),
+        Err(nom::Err::Error(e)) | Err(nom::Err::Failure(e)) => Err(parse_error(&input, e)),
+        Err(e) => Err(err!(Parse, "{e}")),
+        Ok((_, pipelines)) => Ok(pipelines
+            .into_iter()
+            .map(|steps| {
+                let (locations, exprs) = steps
+                    .into_iter()
+                    .map(|(pos, expr)| (Location::new(&input, pos), expr))
+                    .unzip();
+                Expr::Pipeline(exprs, locations)
+            })
+            .collect()),
+    }
+}
+
+/// Converts a parser error to an error located at the failing input.
+fn parse_error(input: &str, e: VerboseError<&str>) -> Error {
+    // The first error is the innermost position where parsing failed.
+    let location = e
+        .errors
+        .iter()
+        .find(|(_, kind)| !matches!(kind, VerboseErrorKind::Context(_)))
+        .or(e.errors.first())
+        .map(|(pos, _)| Location::new(input, pos));
+
+    let error = err!(Parse, "{}", convert_error(input, e));
+    match location {
+        Some(location) => error.at(location, None),
+        None => error,
     }
 }
 
@@ -648,16 +841,16 @@ mod tests {
                   pre_function: mutate(2)
                     pre_binary_op: Assign
                       identifier: distance
-                      pre_binary_op: Divide
-                        number: 9.8
+                      pre_binary_op: Multiply
                         pre_binary_op: Multiply
-                          number: 2
-                          pre_binary_op: Multiply
-                            identifier: time
-                            identifier: time
-                          post_binary_op: Multiply
+                          pre_binary_op: Divide
+                            number: 9.8
+                            number: 2
+                          post_binary_op: Divide
+                          identifier: time
                         post_binary_op: Multiply
-                      post_binary_op: Divide
+                        identifier: time
+                      post_binary_op: Multiply
                     post_binary_op: Assign
                     pre_binary_op: Assign
                       identifier: group_id
@@ -969,4 +1162,278 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn case_when_formulas() {
+        let text = indoc! {r#"
+            mutate(size = case_when(n < 10 ~ "small", n < 100 & m > 2 ~ "medium", true ~ "large"))
+        "#};
+
+        assert_parser!(
+            text,
+            indoc!(
+                r#"
+                pre_pipeline
+                  pre_function: mutate(1)
+                    pre_binary_op: Assign
+                      identifier: size
+                      pre_function: case_when(3)
+                        pre_binary_op: Formula
+                          pre_binary_op: Lt
+                            identifier: n
+                            number: 10
+                          post_binary_op: Lt
+                          string: small
+                        post_binary_op: Formula
+                        pre_binary_op: Formula
+                          pre_binary_op: And
+                            pre_binary_op: Lt
+                              identifier: n
+                              number: 100
+                            post_binary_op: Lt
+                            pre_binary_op: Gt
+                              identifier: m
+                              number: 2
+                            post_binary_op: Gt
+                          post_binary_op: And
+                          string: medium
+                        post_binary_op: Formula
+                        pre_binary_op: Formula
+                          identifier: true
+                          string: large
+                        post_binary_op: Formula
+                      post_function: case_when(3)
+                    post_binary_op: Assign
+                  post_function: mutate(1)
+                post_pipeline"#
+            )
+        );
+    }
+
+    #[test]
+    fn arith_precedence() {
+        let text = indoc! {r#"
+            mutate(z = a + b * c ^ 2 ^ d - e)
+        "#};
+
+        assert_parser!(
+            text,
+            indoc!(
+                r#"
+                pre_pipeline
+                  pre_function: mutate(1)
+                    pre_binary_op: Assign
+                      identifier: z
+                      pre_binary_op: Minus
+                        pre_binary_op: Plus
+                          identifier: a
+                          pre_binary_op: Multiply
+                            identifier: b
+                            pre_binary_op: Pow
+                              identifier: c
+                              pre_binary_op: Pow
+                                number: 2
+                                identifier: d
+                              post_binary_op: Pow
+                            post_binary_op: Pow
+                          post_binary_op: Multiply
+                        post_binary_op: Plus
+                        identifier: e
+                      post_binary_op: Minus
+                    post_binary_op: Assign
+                  post_function: mutate(1)
+                post_pipeline"#
+            )
+        );
+    }
+
+    #[test]
+    fn lists() {
+        let text = indoc! {r#"
+            separate(x, into = (a, b), sep = "-") | filter((a + 1) > b)
+        "#};
+
+        assert_parser!(
+            text,
+            indoc!(
+                r#"
+                pre_pipeline
+                  pre_function: separate(3)
+                    identifier: x
+                    pre_binary_op: Assign
+                      identifier: into
+                      pre_list(2)
+                        identifier: a
+                        identifier: b
+                      post_list(2)
+                    post_binary_op: Assign
+                    pre_binary_op: Assign
+                      identifier: sep
+                      string: -
+                    post_binary_op: Assign
+                  post_function: separate(3)
+                  pre_function: filter(1)
+                    pre_binary_op: Gt
+                      pre_binary_op: Plus
+                        identifier: a
+                        number: 1
+                      post_binary_op: Plus
+                      identifier: b
+                    post_binary_op: Gt
+                  post_function: filter(1)
+                post_pipeline"#
+            )
+        );
+    }
+
+    #[test]
+    fn membership() {
+        let text = indoc! {r#"
+            filter(x %in% ["a", "b"] | x %in% df.x)
+        "#};
+
+        assert_parser!(
+            text,
+            indoc!(
+                r#"
+                pre_pipeline
+                  pre_function: filter(1)
+                    pre_binary_op: Or
+                      pre_binary_op: In
+                        identifier: x
+                        pre_list(2)
+                          string: a
+                          string: b
+                        post_list(2)
+                      post_binary_op: In
+                      pre_binary_op: In
+                        identifier: x
+                        identifier: df.x
+                      post_binary_op: In
+                    post_binary_op: Or
+                  post_function: filter(1)
+                post_pipeline"#
+            )
+        );
+    }
+
+    #[test]
+    fn negative_numbers() {
+        let text = indoc! {r#"
+            summarize(a = nth(x, -2), b = -y)
+        "#};
+
+        assert_parser!(
+            text,
+            indoc!(
+                r#"
+                pre_pipeline
+                  pre_function: summarize(2)
+                    pre_binary_op: Assign
+                      identifier: a
+                      pre_function: nth(2)
+                        identifier: x
+                        pre_unary_op: Minus
+                          number: 2
+                        post_unary_op: Minus
+                      post_function: nth(2)
+                    post_binary_op: Assign
+                    pre_binary_op: Assign
+                      identifier: b
+                      pre_unary_op: Minus
+                        identifier: y
+                      post_unary_op: Minus
+                    post_binary_op: Assign
+                  post_function: summarize(2)
+                post_pipeline"#
+            )
+        );
+    }
+
+    #[test]
+    fn unary_minus_precedence() {
+        let text = indoc! {r#"
+            mutate(a = -2 ^ 2, b = 2 * -x, c = -x ^ 2 + 1)
+        "#};
+
+        assert_parser!(
+            text,
+            indoc!(
+                r#"
+                pre_pipeline
+                  pre_function: mutate(3)
+                    pre_binary_op: Assign
+                      identifier: a
+                      pre_unary_op: Minus
+                        pre_binary_op: Pow
+                          number: 2
+                          number: 2
+                        post_binary_op: Pow
+                      post_unary_op: Minus
+                    post_binary_op: Assign
+                    pre_binary_op: Assign
+                      identifier: b
+                      pre_binary_op: Multiply
+                        number: 2
+                        pre_unary_op: Minus
+                          identifier: x
+                        post_unary_op: Minus
+                      post_binary_op: Multiply
+                    post_binary_op: Assign
+                    pre_binary_op: Assign
+                      identifier: c
+                      pre_binary_op: Plus
+                        pre_unary_op: Minus
+                          pre_binary_op: Pow
+                            identifier: x
+                            number: 2
+                          post_binary_op: Pow
+                        post_unary_op: Minus
+                        number: 1
+                      post_binary_op: Plus
+                    post_binary_op: Assign
+                  post_function: mutate(3)
+                post_pipeline"#
+            )
+        );
+    }
+
+    #[test]
+    fn display_unary_minus() {
+        let exprs = parse("mutate(a = -(x ^ 2), b = -x * 2, c = nth(x, -2))").unwrap();
+        assert_eq!(
+            exprs[0].to_string(),
+            "mutate(a = -(x ^ 2), b = -x * 2, c = nth(x, -2))"
+        );
+    }
+
+    #[test]
+    fn compare_expressions() {
+        let text = indoc! {r#"
+            filter(a * 2 > len(b) + 1)
+        "#};
+
+        assert_parser!(
+            text,
+            indoc!(
+                r#"
+                pre_pipeline
+                  pre_function: filter(1)
+                    pre_binary_op: Gt
+                      pre_binary_op: Multiply
+                        identifier: a
+                        number: 2
+                      post_binary_op: Multiply
+                      pre_binary_op: Plus
+                        pre_function: len(1)
+                          identifier: b
+                        post_function: len(1)
+                        number: 1
+                      post_binary_op: Plus
+                    post_binary_op: Gt
+                  post_function: filter(1)
+                post_pipeline"#
+            )
+        );
+    }
 }
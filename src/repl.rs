@@ -4,8 +4,9 @@
 //! REPL for dply expressions.
 use anyhow::{anyhow, Result};
 use reedline::*;
+use std::panic::{self, AssertUnwindSafe};
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
 
 use crate::{engine, fuzzy, parser, signatures, typing};
 
@@ -84,15 +85,18 @@ impl Evaluator {
             let pipelines = parser::parse(input)?;
             typing::validate(&pipelines)?;
 
-            let mut ctx = self.ctx.lock().unwrap();
-            engine::eval(&mut ctx, &pipelines)?;
+            // Errors from polars internals may still panic, keep the REPL
+            // running and report them as any other evaluation error.
+            let mut ctx = self.lock();
+            panic::catch_unwind(AssertUnwindSafe(|| engine::eval(&mut ctx, &pipelines)))
+                .map_err(|e| anyhow!("Evaluation failed: {}", panic_message(&e)))??;
         }
 
         Ok(())
     }
 
     fn completions(&self, pattern: &str) -> Vec<String> {
-        let ctx = self.ctx.lock().unwrap();
+        let ctx = self.lock();
 
         // If pattern starts with a dot only complete columns and variables.
         let mut completions = if pattern.starts_with('.') {
@@ -112,6 +116,19 @@ impl Evaluator {
         completions.retain(|s| matcher.is_match(s));
         completions
     }
+
+    /// Locks the context, a previous evaluation panic doesn't poison the REPL.
+    fn lock(&self) -> MutexGuard<'_, engine::Context> {
+        self.ctx.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(|s| s.as_str()))
+        .unwrap_or("unknown error")
 }
 
 fn add_menu_keybindings(keybindings: &mut Keybindings) {
@@ -139,7 +156,7 @@ impl Completer for CustomCompleter {
     fn complete(&mut self, line: &str, pos: usize) -> Vec<Suggestion> {
         let line = &line[..pos];
         let prefix_pos = line
-            .rfind(&[',', '|', ' ', '\t', '\n'])
+            .rfind([',', '|', ' ', '\t', '\n'])
             .map(|p| p + 1)
             .unwrap_or(0);
 
@@ -161,7 +178,7 @@ impl Completer for CustomCompleter {
                 .collect()
         } else {
             let prefix_pos = line
-                .rfind(&['(', ',', '|', ' ', '\t', '\n'])
+                .rfind(['(', ',', '|', ' ', '\t', '\n'])
                 .map(|p| p + 1)
                 .unwrap_or(0);
 
@@ -15,10 +15,12 @@ pub fn functions() -> &'static SignaturesMap {
         let mut signatures = HashMap::new();
 
         def_arrange(&mut signatures);
+        def_bind(&mut signatures);
         def_config(&mut signatures);
         def_count(&mut signatures);
         def_csv(&mut signatures);
         def_distinct(&mut signatures);
+        def_drop_nulls(&mut signatures);
         def_filter(&mut signatures);
         def_glimpse(&mut signatures);
         def_group_by(&mut signatures);
@@ -27,11 +29,18 @@ pub fn functions() -> &'static SignaturesMap {
         def_json(&mut signatures);
         def_mutate(&mut signatures);
         def_parquet(&mut signatures);
+        def_pivot(&mut signatures);
         def_relocate(&mut signatures);
         def_rename(&mut signatures);
         def_show(&mut signatures);
         def_select(&mut signatures);
+        def_separate(&mut signatures);
+        def_setops(&mut signatures);
+        def_slice(&mut signatures);
         def_summarize(&mut signatures);
+        def_tail(&mut signatures);
+        def_ungroup(&mut signatures);
+        def_unite(&mut signatures);
         def_unnest(&mut signatures);
 
         signatures
@@ -125,6 +134,62 @@ impl Args {
         names.dedup();
         names
     }
+
+    /// Returns the names of the named arguments like `overwrite` in `overwrite = true`.
+    pub fn named(&self) -> Vec<&'static str> {
+        let mut names = Vec::new();
+
+        match self {
+            Args::NoneOrOne(arg) | Args::ZeroOrMore(arg) | Args::OneOrMore(arg) => {
+                names.extend(arg.named())
+            }
+            Args::OneThenMore(first, rest) => {
+                names.extend(first.named());
+                names.extend(rest.named());
+            }
+            Args::Ordered(args) => {
+                for arg in args {
+                    names.extend(arg.named());
+                }
+            }
+            Args::None => {}
+        }
+
+        names
+    }
+
+    /// Returns the names of the functions that can be used in the arguments.
+    pub fn functions(&self) -> Vec<&'static str> {
+        let mut names = Vec::new();
+
+        match self {
+            Args::NoneOrOne(arg) | Args::ZeroOrMore(arg) | Args::OneOrMore(arg) => {
+                names.extend(arg.functions())
+            }
+            Args::OneThenMore(first, rest) => {
+                names.extend(first.functions());
+                names.extend(rest.functions());
+            }
+            Args::Ordered(args) => {
+                for arg in args {
+                    names.extend(arg.functions());
+                }
+            }
+            Args::None => {}
+        }
+
+        names
+    }
+
+    /// Returns true if the arguments contain a nested expression.
+    pub fn has_expr(&self) -> bool {
+        match self {
+            Args::NoneOrOne(arg) | Args::ZeroOrMore(arg) | Args::OneOrMore(arg) => arg.has_expr(),
+            Args::OneThenMore(first, rest) => first.has_expr() || rest.has_expr(),
+            Args::Ordered(args) => args.iter().any(ArgType::has_expr),
+            Args::None => false,
+        }
+    }
 }
 
 /// Function argument type.
@@ -140,10 +205,19 @@ pub enum ArgType {
     Compare(Box<ArgType>, Box<ArgType>),
     /// An equality expression.
     Eq(Box<ArgType>, Box<ArgType>),
+    /// A mutate expression, function arguments of this type can be nested
+    /// expressions that are checked against [`expr`].
+    Expr,
+    /// A formula expression `condition ~ value`.
+    Formula(Box<ArgType>, Box<ArgType>),
     /// A function call expression.
     Function(&'static str, Box<Args>),
     /// An identifier expression.
     Identifier,
+    /// A membership expression `x %in% values`.
+    In(Box<ArgType>, Box<ArgType>),
+    /// A list of values `(a, b, c)`.
+    List(Box<ArgType>),
     /// A logical expression.
     Logical(Box<ArgType>),
     /// A named identifier.
@@ -179,6 +253,21 @@ impl ArgType {
         Self::Eq(lhs.into(), rhs.into())
     }
 
+    /// Creates a formula type (~).
+    fn formula(lhs: ArgType, rhs: ArgType) -> Self {
+        Self::Formula(lhs.into(), rhs.into())
+    }
+
+    /// Creates a membership type (%in%).
+    fn is_in(lhs: ArgType, rhs: ArgType) -> Self {
+        Self::In(lhs.into(), rhs.into())
+    }
+
+    /// Creates a list type.
+    fn list(arg: ArgType) -> Self {
+        Self::List(arg.into())
+    }
+
     /// Creates a logical type (&, |).
     fn logical(arg: ArgType) -> Self {
         Self::Logical(arg.into())
@@ -208,7 +297,7 @@ impl ArgType {
                 names.extend(lhs.names());
                 names.extend(rhs.names());
             }
-            ArgType::Eq(lhs, rhs) => {
+            ArgType::Eq(lhs, rhs) | ArgType::Formula(lhs, rhs) | ArgType::In(lhs, rhs) => {
                 names.extend(lhs.names());
                 names.extend(rhs.names());
             }
@@ -222,7 +311,7 @@ impl ArgType {
                 names.push(name);
                 names.extend(args.names());
             }
-            ArgType::Logical(arg) => names.extend(arg.names()),
+            ArgType::List(arg) | ArgType::Logical(arg) => names.extend(arg.names()),
             ArgType::Named(name) => names.push(name.to_string()),
             ArgType::Negate(arg) => names.extend(arg.names()),
             ArgType::OneOf(args) => {
@@ -235,6 +324,67 @@ impl ArgType {
 
         names
     }
+
+    /// Returns true if this type contains a nested expression.
+    fn has_expr(&self) -> bool {
+        match self {
+            ArgType::Expr => true,
+            ArgType::Arith(arg)
+            | ArgType::List(arg)
+            | ArgType::Logical(arg)
+            | ArgType::Negate(arg) => arg.has_expr(),
+            ArgType::Assign(lhs, rhs)
+            | ArgType::Compare(lhs, rhs)
+            | ArgType::Eq(lhs, rhs)
+            | ArgType::Formula(lhs, rhs)
+            | ArgType::In(lhs, rhs) => lhs.has_expr() || rhs.has_expr(),
+            ArgType::Function(_, args) => args.has_expr(),
+            ArgType::OneOf(args) => args.iter().any(ArgType::has_expr),
+            _ => false,
+        }
+    }
+
+    /// Extracts the names of functions, including nested functions.
+    pub fn functions(&self) -> Vec<&'static str> {
+        let mut names = Vec::new();
+
+        match self {
+            ArgType::Arith(arg)
+            | ArgType::List(arg)
+            | ArgType::Logical(arg)
+            | ArgType::Negate(arg) => names.extend(arg.functions()),
+            ArgType::Assign(lhs, rhs)
+            | ArgType::Compare(lhs, rhs)
+            | ArgType::Eq(lhs, rhs)
+            | ArgType::Formula(lhs, rhs)
+            | ArgType::In(lhs, rhs) => {
+                names.extend(lhs.functions());
+                names.extend(rhs.functions());
+            }
+            ArgType::Function(name, args) => {
+                names.push(name);
+                names.extend(args.functions());
+            }
+            ArgType::OneOf(args) => {
+                for arg in args {
+                    names.extend(arg.functions());
+                }
+            }
+            _ => {}
+        }
+
+        names
+    }
+
+    /// Extracts the names of named arguments, nested functions are not included.
+    fn named(&self) -> Vec<&'static str> {
+        match self {
+            ArgType::Assign(lhs, _) => lhs.named(),
+            ArgType::Named(name) => vec![name],
+            ArgType::OneOf(args) => args.iter().flat_map(|arg| arg.named()).collect(),
+            _ => Vec::new(),
+        }
+    }
 }
 
 fn def_arrange(signatures: &mut SignaturesMap) {
@@ -247,6 +397,17 @@ fn def_arrange(signatures: &mut SignaturesMap) {
     );
 }
 
+fn def_bind(signatures: &mut SignaturesMap) {
+    signatures.insert("bind_cols", Args::OneOrMore(ArgType::Identifier));
+    signatures.insert(
+        "bind_rows",
+        Args::OneOrMore(ArgType::OneOf(vec![
+            ArgType::Identifier,
+            ArgType::assign(ArgType::Named("id"), ArgType::Identifier),
+        ])),
+    );
+}
+
 fn def_config(signatures: &mut SignaturesMap) {
     signatures.insert(
         "config",
@@ -282,44 +443,103 @@ fn def_distinct(signatures: &mut SignaturesMap) {
     signatures.insert("distinct", Args::OneOrMore(ArgType::Identifier));
 }
 
+fn def_drop_nulls(signatures: &mut SignaturesMap) {
+    signatures.insert("drop_nulls", Args::ZeroOrMore(ArgType::Identifier));
+}
+
 fn def_filter(signatures: &mut SignaturesMap) {
-    let compare_args = ArgType::compare(
-        ArgType::Identifier,
-        ArgType::OneOf(vec![
-            ArgType::Identifier,
-            ArgType::Number,
-            ArgType::String,
-            ArgType::Bool,
-            ArgType::function("dt", Args::Ordered(vec![ArgType::String])),
-        ]),
-    );
+    signatures.insert("filter", Args::OneOrMore(condition()));
+}
+
+/// Returns the conditions used by filter and by mutate conditionals.
+fn condition() -> ArgType {
+    // Both sides of a comparison can be any mutate expression.
+    let operand = ArgType::OneOf(vec![
+        ArgType::Expr,
+        ArgType::Bool,
+        ArgType::function("dt", Args::Ordered(vec![ArgType::String])),
+    ]);
+    let compare_args = ArgType::compare(operand.clone(), operand);
 
     let contains_fn = ArgType::function(
         "contains",
-        Args::Ordered(vec![
+        Args::OneThenMore(
             ArgType::Identifier,
-            ArgType::OneOf(vec![ArgType::String, ArgType::Number]),
-        ]),
+            ArgType::OneOf(vec![
+                ArgType::String,
+                ArgType::Number,
+                ArgType::assign(ArgType::Named("literal"), ArgType::Bool),
+            ]),
+        ),
+    );
+
+    let starts_with_fn = ArgType::function(
+        "starts_with",
+        Args::Ordered(vec![ArgType::Identifier, ArgType::String]),
+    );
+    let ends_with_fn = ArgType::function(
+        "ends_with",
+        Args::Ordered(vec![ArgType::Identifier, ArgType::String]),
+    );
+    let matches_fn = ArgType::function(
+        "matches",
+        Args::OneThenMore(
+            ArgType::Identifier,
+            ArgType::OneOf(vec![
+                ArgType::String,
+                ArgType::assign(ArgType::Named("ignore_case"), ArgType::Bool),
+            ]),
+        ),
+    );
+    let str_equals_ci_fn = ArgType::function(
+        "str_equals_ci",
+        Args::Ordered(vec![ArgType::Identifier, ArgType::String]),
     );
 
     let is_null_fn = ArgType::function("is_null", Args::Ordered(vec![ArgType::Identifier]));
 
-    let predicates = ArgType::OneOf(vec![
-        contains_fn.clone(),
-        ArgType::negate(contains_fn),
-        is_null_fn.clone(),
-        ArgType::negate(is_null_fn),
+    // Values are a list of literals or a column of another dataframe `df.col`.
+    let values = ArgType::OneOf(vec![
+        ArgType::list(ArgType::OneOf(vec![ArgType::Number, ArgType::String])),
+        ArgType::Number,
+        ArgType::String,
+        ArgType::Identifier,
     ]);
 
-    let filter_arg = ArgType::OneOf(vec![compare_args, predicates]);
+    let in_args = ArgType::is_in(ArgType::Identifier, values.clone());
+    let is_in_fn = ArgType::function("is_in", Args::Ordered(vec![ArgType::Identifier, values]));
 
-    signatures.insert(
-        "filter",
-        Args::OneOrMore(ArgType::OneOf(vec![
-            filter_arg.clone(),
-            ArgType::logical(filter_arg),
-        ])),
+    let bound = ArgType::OneOf(vec![
+        ArgType::Identifier,
+        ArgType::Number,
+        ArgType::String,
+        ArgType::function("dt", Args::Ordered(vec![ArgType::String])),
+    ]);
+    let between_fn = ArgType::function(
+        "between",
+        Args::Ordered(vec![ArgType::Identifier, bound.clone(), bound]),
     );
+
+    let predicates = ArgType::OneOf(
+        [
+            contains_fn,
+            starts_with_fn,
+            ends_with_fn,
+            matches_fn,
+            str_equals_ci_fn,
+            is_null_fn,
+            in_args,
+            is_in_fn,
+            between_fn,
+        ]
+        .into_iter()
+        .flat_map(|pred| [pred.clone(), ArgType::negate(pred)])
+        .collect(),
+    );
+
+    let filter_arg = ArgType::OneOf(vec![compare_args, predicates]);
+
+    ArgType::OneOf(vec![filter_arg.clone(), ArgType::logical(filter_arg)])
 }
 
 fn def_glimpse(signatures: &mut SignaturesMap) {
@@ -337,14 +557,41 @@ fn def_head(signatures: &mut SignaturesMap) {
 fn def_joins(signatures: &mut SignaturesMap) {
     let args = Args::OneThenMore(
         ArgType::Identifier,
-        ArgType::eq(ArgType::Identifier, ArgType::Identifier),
+        ArgType::OneOf(vec![
+            ArgType::compare(ArgType::Identifier, ArgType::Identifier),
+            ArgType::assign(ArgType::Named("suffix"), ArgType::String),
+            ArgType::assign(ArgType::Named("validate"), ArgType::String),
+        ]),
     );
 
     signatures.insert("anti_join", args.clone());
+    signatures.insert(
+        "asof_join",
+        Args::OneThenMore(
+            ArgType::Identifier,
+            ArgType::OneOf(vec![
+                ArgType::eq(ArgType::Identifier, ArgType::Identifier),
+                ArgType::assign(
+                    ArgType::Named("by"),
+                    ArgType::OneOf(vec![
+                        ArgType::Identifier,
+                        ArgType::list(ArgType::Identifier),
+                    ]),
+                ),
+                ArgType::assign(ArgType::Named("strategy"), ArgType::String),
+                ArgType::assign(
+                    ArgType::Named("tolerance"),
+                    ArgType::OneOf(vec![ArgType::Number, ArgType::String]),
+                ),
+                ArgType::assign(ArgType::Named("suffix"), ArgType::String),
+            ]),
+        ),
+    );
     signatures.insert("cross_join", args.clone());
     signatures.insert("inner_join", args.clone());
     signatures.insert("left_join", args.clone());
-    signatures.insert("outer_join", args);
+    signatures.insert("outer_join", args.clone());
+    signatures.insert("semi_join", args);
 }
 
 fn def_json(signatures: &mut SignaturesMap) {
@@ -361,11 +608,53 @@ fn def_json(signatures: &mut SignaturesMap) {
 }
 
 fn def_mutate(signatures: &mut SignaturesMap) {
+    signatures.insert(
+        "mutate",
+        Args::OneOrMore(ArgType::assign(ArgType::Identifier, expr().clone())),
+    );
+}
+
+/// Returns the expressions that can be assigned to a column in mutate.
+pub fn expr() -> &'static ArgType {
+    static EXPR: OnceLock<ArgType> = OnceLock::new();
+    EXPR.get_or_init(def_expr)
+}
+
+fn def_expr() -> ArgType {
     let operand = ArgType::OneOf(vec![
         ArgType::Identifier,
         ArgType::Number,
         ArgType::String,
         ArgType::function("ymd_hms", Args::Ordered(vec![ArgType::Identifier])),
+        ArgType::function("ymd", Args::Ordered(vec![ArgType::Expr])),
+        ArgType::function("dmy", Args::Ordered(vec![ArgType::Expr])),
+        ArgType::function("mdy", Args::Ordered(vec![ArgType::Expr])),
+        ArgType::function("year", Args::Ordered(vec![ArgType::Expr])),
+        ArgType::function("quarter", Args::Ordered(vec![ArgType::Expr])),
+        ArgType::function("month", Args::Ordered(vec![ArgType::Expr])),
+        ArgType::function("day", Args::Ordered(vec![ArgType::Expr])),
+        ArgType::function("hour", Args::Ordered(vec![ArgType::Expr])),
+        ArgType::function("minute", Args::Ordered(vec![ArgType::Expr])),
+        ArgType::function("second", Args::Ordered(vec![ArgType::Expr])),
+        ArgType::function("weekday", Args::Ordered(vec![ArgType::Expr])),
+        ArgType::function("week", Args::Ordered(vec![ArgType::Expr])),
+        ArgType::function("day_of_year", Args::Ordered(vec![ArgType::Expr])),
+        ArgType::function(
+            "floor_date",
+            Args::Ordered(vec![ArgType::Expr, ArgType::String]),
+        ),
+        ArgType::function(
+            "ceil_date",
+            Args::Ordered(vec![ArgType::Expr, ArgType::String]),
+        ),
+        ArgType::function(
+            "strftime",
+            Args::Ordered(vec![ArgType::Expr, ArgType::String]),
+        ),
+        ArgType::function(
+            "date_diff",
+            Args::Ordered(vec![ArgType::Expr, ArgType::Expr, ArgType::String]),
+        ),
         ArgType::function("dnanos", Args::Ordered(vec![ArgType::Identifier])),
         ArgType::function("dmicros", Args::Ordered(vec![ArgType::Identifier])),
         ArgType::function("dmillis", Args::Ordered(vec![ArgType::Identifier])),
@@ -383,15 +672,112 @@ fn def_mutate(signatures: &mut SignaturesMap) {
         ArgType::function("mean", Args::Ordered(vec![ArgType::Identifier])),
         ArgType::function("median", Args::Ordered(vec![ArgType::Identifier])),
         ArgType::function("min", Args::Ordered(vec![ArgType::Identifier])),
+        ArgType::function("sd", Args::Ordered(vec![ArgType::Identifier])),
+        ArgType::function("sum", Args::Ordered(vec![ArgType::Identifier])),
+        ArgType::function("var", Args::Ordered(vec![ArgType::Identifier])),
         ArgType::function("row", Args::None),
+        ArgType::function("abs", Args::Ordered(vec![ArgType::Expr])),
+        ArgType::function("round", Args::OneThenMore(ArgType::Expr, ArgType::Number)),
+        ArgType::function("floor", Args::Ordered(vec![ArgType::Expr])),
+        ArgType::function("ceil", Args::Ordered(vec![ArgType::Expr])),
+        ArgType::function("sqrt", Args::Ordered(vec![ArgType::Expr])),
+        ArgType::function("pow", Args::Ordered(vec![ArgType::Expr, ArgType::Expr])),
+        ArgType::function("exp", Args::Ordered(vec![ArgType::Expr])),
+        ArgType::function("log", Args::Ordered(vec![ArgType::Expr])),
+        ArgType::function("log10", Args::Ordered(vec![ArgType::Expr])),
+        ArgType::function("log2", Args::Ordered(vec![ArgType::Expr])),
+        ArgType::function("sign", Args::Ordered(vec![ArgType::Expr])),
+        ArgType::function(
+            "clip",
+            Args::Ordered(vec![ArgType::Expr, ArgType::Expr, ArgType::Expr]),
+        ),
+        ArgType::function("sin", Args::Ordered(vec![ArgType::Expr])),
+        ArgType::function("cos", Args::Ordered(vec![ArgType::Expr])),
+        ArgType::function("tan", Args::Ordered(vec![ArgType::Expr])),
+        ArgType::function("asin", Args::Ordered(vec![ArgType::Expr])),
+        ArgType::function("acos", Args::Ordered(vec![ArgType::Expr])),
+        ArgType::function("atan", Args::Ordered(vec![ArgType::Expr])),
+        ArgType::function("to_upper", Args::Ordered(vec![ArgType::Expr])),
+        ArgType::function("to_lower", Args::Ordered(vec![ArgType::Expr])),
+        ArgType::function("trim", Args::Ordered(vec![ArgType::Expr])),
+        ArgType::function(
+            "replace",
+            Args::Ordered(vec![ArgType::Expr, ArgType::String, ArgType::String]),
+        ),
+        ArgType::function(
+            "replace_all",
+            Args::Ordered(vec![ArgType::Expr, ArgType::String, ArgType::String]),
+        ),
+        ArgType::function(
+            "substr",
+            Args::Ordered(vec![ArgType::Expr, ArgType::Number, ArgType::Number]),
+        ),
+        ArgType::function(
+            "str_pad",
+            Args::OneThenMore(
+                ArgType::Expr,
+                ArgType::OneOf(vec![
+                    ArgType::Number,
+                    ArgType::assign(ArgType::Named("side"), ArgType::String),
+                    ArgType::assign(ArgType::Named("pad"), ArgType::String),
+                ]),
+            ),
+        ),
+        ArgType::function("split", Args::Ordered(vec![ArgType::Expr, ArgType::String])),
+        ArgType::function("concat", Args::OneOrMore(ArgType::Expr)),
+        ArgType::function(
+            "extract",
+            Args::Ordered(vec![ArgType::Expr, ArgType::String, ArgType::Number]),
+        ),
+        cast_fn("as_int", &[]),
+        cast_fn("as_float", &[]),
+        cast_fn("as_str", &[]),
+        cast_fn("as_bool", &[]),
+        cast_fn("as_date", &[ArgType::String]),
+        cast_fn("as_datetime", &[ArgType::String]),
+        cast_fn("as_category", &[]),
+        ArgType::function("row_number", Args::None),
+        ArgType::function("lag", Args::OneThenMore(ArgType::Expr, ArgType::Number)),
+        ArgType::function("lead", Args::OneThenMore(ArgType::Expr, ArgType::Number)),
+        ArgType::function("cumsum", Args::Ordered(vec![ArgType::Expr])),
+        ArgType::function("cumprod", Args::Ordered(vec![ArgType::Expr])),
+        ArgType::function("cummax", Args::Ordered(vec![ArgType::Expr])),
+        ArgType::function("cummin", Args::Ordered(vec![ArgType::Expr])),
+        ArgType::function("diff", Args::Ordered(vec![ArgType::Expr])),
+        ArgType::function("pct_change", Args::Ordered(vec![ArgType::Expr])),
+        ArgType::function("rank", Args::OneThenMore(ArgType::Expr, ArgType::String)),
+        ArgType::function("dense_rank", Args::Ordered(vec![ArgType::Expr])),
+        ArgType::function("percent_rank", Args::Ordered(vec![ArgType::Expr])),
+        ArgType::function("ntile", Args::Ordered(vec![ArgType::Expr, ArgType::Number])),
+        ArgType::function("coalesce", Args::OneOrMore(ArgType::Expr)),
+        ArgType::function(
+            "fill_null",
+            Args::Ordered(vec![ArgType::Expr, ArgType::Expr]),
+        ),
+        ArgType::function(
+            "if_else",
+            Args::Ordered(vec![condition(), ArgType::Expr, ArgType::Expr]),
+        ),
+        ArgType::function(
+            "case_when",
+            Args::OneOrMore(ArgType::formula(
+                ArgType::OneOf(vec![condition(), ArgType::Bool]),
+                ArgType::Expr,
+            )),
+        ),
     ]);
 
-    let expr = ArgType::OneOf(vec![operand.clone(), ArgType::arith(operand)]);
+    ArgType::OneOf(vec![operand.clone(), ArgType::arith(operand)])
+}
 
-    signatures.insert(
-        "mutate",
-        Args::OneOrMore(ArgType::assign(ArgType::Identifier, expr)),
-    );
+/// A cast function with an optional `strict = false` argument.
+fn cast_fn(name: &'static str, extra: &[ArgType]) -> ArgType {
+    let mut optional = extra.to_vec();
+    optional.push(ArgType::assign(ArgType::Named("strict"), ArgType::Bool));
+    ArgType::function(
+        name,
+        Args::OneThenMore(ArgType::Expr, ArgType::OneOf(optional)),
+    )
 }
 
 fn def_parquet(signatures: &mut SignaturesMap) {
@@ -404,6 +790,40 @@ fn def_parquet(signatures: &mut SignaturesMap) {
     );
 }
 
+fn def_pivot(signatures: &mut SignaturesMap) {
+    let contains_fn = ArgType::function("contains", Args::Ordered(vec![ArgType::String]));
+    let ends_with_fn = ArgType::function("ends_with", Args::Ordered(vec![ArgType::String]));
+    let start_with_fn = ArgType::function("starts_with", Args::Ordered(vec![ArgType::String]));
+
+    signatures.insert(
+        "pivot_longer",
+        Args::OneOrMore(ArgType::OneOf(vec![
+            ArgType::Identifier,
+            contains_fn.clone(),
+            ArgType::negate(contains_fn),
+            ends_with_fn.clone(),
+            ArgType::negate(ends_with_fn),
+            start_with_fn.clone(),
+            ArgType::negate(start_with_fn),
+            ArgType::assign(ArgType::Named("names_to"), ArgType::Identifier),
+            ArgType::assign(ArgType::Named("values_to"), ArgType::Identifier),
+        ])),
+    );
+
+    signatures.insert(
+        "pivot_wider",
+        Args::OneOrMore(ArgType::OneOf(vec![
+            ArgType::assign(ArgType::Named("names_from"), ArgType::Identifier),
+            ArgType::assign(ArgType::Named("values_from"), ArgType::Identifier),
+            ArgType::assign(ArgType::Named("values_fn"), ArgType::Identifier),
+            ArgType::assign(
+                ArgType::Named("values_fill"),
+                ArgType::OneOf(vec![ArgType::Number, ArgType::String]),
+            ),
+        ])),
+    );
+}
+
 fn def_relocate(signatures: &mut SignaturesMap) {
     signatures.insert(
         "relocate",
@@ -443,26 +863,51 @@ fn def_select(signatures: &mut SignaturesMap) {
 }
 
 fn def_summarize(signatures: &mut SignaturesMap) {
+    // Aggregates take any mutate expression or condition like `mean(delay > 15)`.
+    let value = ArgType::OneOf(vec![ArgType::Expr, condition()]);
+    let agg = |name, rest: &[ArgType]| {
+        let mut args = vec![value.clone()];
+        args.extend_from_slice(rest);
+        ArgType::function(name, Args::Ordered(args))
+    };
+
+    let aggregates = vec![
+        agg("all", &[]),
+        agg("any", &[]),
+        agg("approx_n_distinct", &[]),
+        agg("cor", std::slice::from_ref(&value)),
+        agg("first", &[]),
+        agg("kurtosis", &[]),
+        agg("last", &[]),
+        agg("list", &[]),
+        agg("max", &[]),
+        agg("mean", &[]),
+        agg("median", &[]),
+        agg("min", &[]),
+        agg("mode", &[]),
+        ArgType::function("n", Args::None),
+        agg("n_distinct", &[]),
+        agg("nth", &[ArgType::Number]),
+        agg("null_count", &[]),
+        agg("quantile", &[ArgType::Number]),
+        agg("sd", &[]),
+        agg("skew", &[]),
+        agg("str_join", &[ArgType::String]),
+        agg("sum", &[]),
+        agg("var", &[]),
+        agg("weighted_mean", std::slice::from_ref(&value)),
+    ];
+
+    // Arithmetic between aggregates like `sum(a) / sum(b)`.
+    let mut operands = aggregates.clone();
+    operands.push(ArgType::Number);
+
+    let mut values = aggregates;
+    values.push(ArgType::arith(ArgType::OneOf(operands)));
+
     signatures.insert(
         "summarize",
-        Args::OneOrMore(ArgType::Assign(
-            Box::new(ArgType::Identifier),
-            Box::new(ArgType::OneOf(vec![
-                ArgType::function("list", Args::Ordered(vec![ArgType::Identifier])),
-                ArgType::function("max", Args::Ordered(vec![ArgType::Identifier])),
-                ArgType::function("mean", Args::Ordered(vec![ArgType::Identifier])),
-                ArgType::function("median", Args::Ordered(vec![ArgType::Identifier])),
-                ArgType::function("min", Args::Ordered(vec![ArgType::Identifier])),
-                ArgType::function("n", Args::None),
-                ArgType::function(
-                    "quantile",
-                    Args::Ordered(vec![ArgType::Identifier, ArgType::Number]),
-                ),
-                ArgType::function("sd", Args::Ordered(vec![ArgType::Identifier])),
-                ArgType::function("sum", Args::Ordered(vec![ArgType::Identifier])),
-                ArgType::function("var", Args::Ordered(vec![ArgType::Identifier])),
-            ])),
-        )),
+        Args::OneOrMore(ArgType::assign(ArgType::Identifier, ArgType::OneOf(values))),
     );
 }
 
@@ -470,6 +915,83 @@ fn def_show(signatures: &mut SignaturesMap) {
     signatures.insert("show", Args::None);
 }
 
+fn def_separate(signatures: &mut SignaturesMap) {
+    signatures.insert(
+        "separate",
+        Args::OneThenMore(
+            ArgType::Identifier,
+            ArgType::OneOf(vec![
+                ArgType::assign(
+                    ArgType::Named("into"),
+                    ArgType::OneOf(vec![
+                        ArgType::Identifier,
+                        ArgType::list(ArgType::Identifier),
+                    ]),
+                ),
+                ArgType::assign(ArgType::Named("sep"), ArgType::String),
+                ArgType::assign(ArgType::Named("remove"), ArgType::Bool),
+            ]),
+        ),
+    );
+}
+
+fn def_setops(signatures: &mut SignaturesMap) {
+    let args = Args::Ordered(vec![ArgType::Identifier]);
+
+    signatures.insert("intersect", args.clone());
+    signatures.insert("setdiff", args.clone());
+    signatures.insert("union", args.clone());
+    signatures.insert("union_all", args);
+}
+
+fn def_slice(signatures: &mut SignaturesMap) {
+    signatures.insert(
+        "slice",
+        Args::Ordered(vec![ArgType::Number, ArgType::Number]),
+    );
+    let count = ArgType::OneOf(vec![
+        ArgType::Number,
+        ArgType::assign(ArgType::Named("n"), ArgType::Number),
+    ]);
+    signatures.insert(
+        "slice_max",
+        Args::OneThenMore(ArgType::Identifier, count.clone()),
+    );
+    signatures.insert("slice_min", Args::OneThenMore(ArgType::Identifier, count));
+    signatures.insert(
+        "slice_sample",
+        Args::ZeroOrMore(ArgType::OneOf(vec![
+            ArgType::Number,
+            ArgType::assign(ArgType::Named("n"), ArgType::Number),
+            ArgType::assign(ArgType::Named("prop"), ArgType::Number),
+            ArgType::assign(ArgType::Named("seed"), ArgType::Number),
+            ArgType::assign(ArgType::Named("replace"), ArgType::Bool),
+        ])),
+    );
+}
+
+fn def_tail(signatures: &mut SignaturesMap) {
+    signatures.insert("tail", Args::NoneOrOne(ArgType::Number));
+}
+
+fn def_ungroup(signatures: &mut SignaturesMap) {
+    signatures.insert("ungroup", Args::None);
+}
+
+fn def_unite(signatures: &mut SignaturesMap) {
+    signatures.insert(
+        "unite",
+        Args::OneThenMore(
+            ArgType::Identifier,
+            ArgType::OneOf(vec![
+                ArgType::Identifier,
+                ArgType::assign(ArgType::Named("sep"), ArgType::String),
+                ArgType::assign(ArgType::Named("remove"), ArgType::Bool),
+            ]),
+        ),
+    );
+}
+
 fn def_unnest(signatures: &mut SignaturesMap) {
     signatures.insert("unnest", Args::OneOrMore(ArgType::Identifier));
 }
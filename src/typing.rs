@@ -2,17 +2,17 @@
 // SPDX-License-Identifier: Apache-2.0
 
 //! Checks pipeline functions and arguments types.
-use anyhow::{anyhow, bail, Result};
-
+use crate::error::{bail, err, Result};
+use crate::fuzzy;
 use crate::parser::{Expr, Operator};
 use crate::signatures::{self, ArgType, Args};
 
 /// Checks pipeline functions and arguments types.
 pub fn validate(exprs: &[Expr]) -> Result<()> {
     for expr in exprs {
-        if let Expr::Pipeline(exprs) = expr {
-            for expr in exprs {
-                check_signature(expr)?;
+        if let Expr::Pipeline(exprs, locations) = expr {
+            for (expr, location) in exprs.iter().zip(locations) {
+                check_signature(expr).map_err(|e| e.at(*location, expr.function_name()))?;
             }
         }
     }
@@ -25,27 +25,31 @@ fn check_signature(expr: &Expr) -> Result<()> {
         Expr::Function(name, expr_args) => {
             let sigs = signatures::functions();
             if let Some(sig_args) = sigs.get(name.as_str()) {
+                check_function_names(name, expr_args, sig_args)?;
                 check_args(name, expr_args, sig_args)
             } else {
-                Err(anyhow!("Unknown function: {name}"))
+                let hint = fuzzy::did_you_mean(name, sigs.keys().copied());
+                Err(err!(Type, "Unknown function: {name}{hint}"))
             }
         }
         Expr::Identifier(_) => Ok(()),
-        _ => Err(anyhow!("Unexpected expression {expr}")),
+        _ => Err(err!(Type, "Unexpected expression {expr}")),
     }
 }
 
 fn check_args(name: &str, exprs: &[Expr], sig_args: &Args) -> Result<()> {
+    check_named_args(name, exprs, sig_args)?;
+
     match sig_args {
         signatures::Args::None => {
             if !exprs.is_empty() {
-                bail!("Unexpected argument for function '{name}'");
+                bail!(Type, "Unexpected argument for function '{name}'");
             }
         }
         signatures::Args::NoneOrOne(arg) => match exprs.len() {
             0 => return Ok(()),
             1 => check_arg(name, &exprs[0], arg)?,
-            _ => bail!("Too many arguments for function '{name}'"),
+            _ => bail!(Type, "Too many arguments for function '{name}'"),
         },
         signatures::Args::ZeroOrMore(arg) => {
             for expr in exprs {
@@ -54,7 +58,7 @@ fn check_args(name: &str, exprs: &[Expr], sig_args: &Args) -> Result<()> {
         }
         signatures::Args::OneOrMore(arg) => {
             if exprs.is_empty() {
-                bail!("Missing arguments for function '{name}'");
+                bail!(Type, "Missing arguments for function '{name}'");
             }
 
             for expr in exprs {
@@ -63,7 +67,7 @@ fn check_args(name: &str, exprs: &[Expr], sig_args: &Args) -> Result<()> {
         }
         signatures::Args::OneThenMore(first, rest) => {
             if exprs.is_empty() {
-                bail!("Missing argument for function '{name}'");
+                bail!(Type, "Missing argument for function '{name}'");
             }
 
             check_arg(name, &exprs[0], first)?;
@@ -74,11 +78,11 @@ fn check_args(name: &str, exprs: &[Expr], sig_args: &Args) -> Result<()> {
         }
         signatures::Args::Ordered(args) => {
             if exprs.len() < args.len() {
-                bail!("Missing arguments for function '{name}'");
+                bail!(Type, "Missing arguments for function '{name}'");
             }
 
             if exprs.len() > args.len() {
-                bail!("Too many arguments for function '{name}'");
+                bail!(Type, "Too many arguments for function '{name}'");
             }
 
             for (expr, arg) in exprs.iter().zip(args.iter()) {
@@ -90,6 +94,71 @@ fn check_args(name: &str, exprs: &[Expr], sig_args: &Args) -> Result<()> {
     Ok(())
 }
 
+/// Checks for misspelled named arguments like `overwite = true`.
+fn check_named_args(fname: &str, exprs: &[Expr], sig_args: &Args) -> Result<()> {
+    let named = sig_args.named();
+
+    for expr in exprs {
+        if let Expr::BinaryOp(lhs, Operator::Assign, _) = expr {
+            match lhs.as_ref() {
+                Expr::Identifier(lhs) if !named.contains(&lhs.as_str()) => {
+                    if let Some(arg) = fuzzy::closest(lhs, named.iter().copied()) {
+                        bail!(
+                            Type,
+                            "Unknown argument '{lhs}' for function '{fname}', did you mean '{arg}'?"
+                        );
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks for unknown functions in arguments like `mutate(x = maen(y))`.
+fn check_function_names(fname: &str, exprs: &[Expr], sig_args: &Args) -> Result<()> {
+    fn used_functions<'a>(expr: &'a Expr, names: &mut Vec<&'a str>) {
+        match expr {
+            Expr::Function(name, args) => {
+                names.push(name);
+                args.iter().for_each(|arg| used_functions(arg, names));
+            }
+            Expr::BinaryOp(lhs, _, rhs) => {
+                used_functions(lhs, names);
+                used_functions(rhs, names);
+            }
+            Expr::UnaryOp(_, expr) => used_functions(expr, names),
+            Expr::List(exprs) => exprs.iter().for_each(|expr| used_functions(expr, names)),
+            _ => {}
+        }
+    }
+
+    let mut functions = sig_args.functions();
+    if sig_args.has_expr() {
+        // Expressions can use any mutate function at any nesting level.
+        functions.extend(signatures::expr().functions());
+    }
+
+    let mut used = Vec::new();
+    exprs
+        .iter()
+        .for_each(|expr| used_functions(expr, &mut used));
+
+    for name in used {
+        if !functions.contains(&name) {
+            let hint = fuzzy::did_you_mean(name, functions.iter().copied());
+            bail!(
+                Type,
+                "Unknown function '{name}' for function '{fname}'{hint}"
+            );
+        }
+    }
+
+    Ok(())
+}
+
 fn check_arg(fname: &str, expr: &Expr, arg: &ArgType) -> Result<()> {
     match arg {
         ArgType::Arith(arg) => check_arith(fname, expr, arg),
@@ -97,8 +166,12 @@ fn check_arg(fname: &str, expr: &Expr, arg: &ArgType) -> Result<()> {
         ArgType::Bool => check_bool(fname, expr),
         ArgType::Compare(lhs, rhs) => check_compare(fname, expr, lhs, rhs),
         ArgType::Eq(lhs, rhs) => check_equal(fname, expr, lhs, rhs),
+        ArgType::Expr => check_arg(fname, expr, signatures::expr()),
+        ArgType::Formula(lhs, rhs) => check_formula(fname, expr, lhs, rhs),
         ArgType::Function(name, args) => check_function(name, expr, args),
         ArgType::Identifier => check_identifier(fname, expr),
+        ArgType::In(lhs, rhs) => check_in(fname, expr, lhs, rhs),
+        ArgType::List(arg) => check_list(fname, expr, arg),
         ArgType::Logical(arg) => check_logical(fname, expr, arg),
         ArgType::Named(name) => check_named(fname, name, expr),
         ArgType::Negate(arg) => check_negate(fname, expr, arg),
@@ -117,6 +190,8 @@ fn check_arith(fname: &str, expr: &Expr, arg: &ArgType) -> Result<()> {
                 | Expr::BinaryOp(_, Operator::Divide, _)
                 | Expr::BinaryOp(_, Operator::Multiply, _)
                 | Expr::BinaryOp(_, Operator::Mod, _)
+                | Expr::BinaryOp(_, Operator::Pow, _)
+                | Expr::UnaryOp(Operator::Minus, _)
         )
     }
 
@@ -125,7 +200,8 @@ fn check_arith(fname: &str, expr: &Expr, arg: &ArgType) -> Result<()> {
         | Expr::BinaryOp(lhs, Operator::Minus, rhs)
         | Expr::BinaryOp(lhs, Operator::Divide, rhs)
         | Expr::BinaryOp(lhs, Operator::Multiply, rhs)
-        | Expr::BinaryOp(lhs, Operator::Mod, rhs) => {
+        | Expr::BinaryOp(lhs, Operator::Mod, rhs)
+        | Expr::BinaryOp(lhs, Operator::Pow, rhs) => {
             if is_arith(lhs) {
                 check_arith(fname, lhs, arg)?;
             } else {
@@ -138,7 +214,17 @@ fn check_arith(fname: &str, expr: &Expr, arg: &ArgType) -> Result<()> {
                 check_arg(fname, rhs, arg)
             }
         }
-        _ => Err(anyhow!("Invalid argument '{expr}' for function '{fname}'")),
+        Expr::UnaryOp(Operator::Minus, operand) => {
+            if is_arith(operand) {
+                check_arith(fname, operand, arg)
+            } else {
+                check_arg(fname, operand, arg)
+            }
+        }
+        _ => Err(err!(
+            Type,
+            "Invalid argument '{expr}' for function '{fname}'"
+        )),
     }
 }
 
@@ -148,7 +234,10 @@ fn check_assign(fname: &str, expr: &Expr, larg: &ArgType, rarg: &ArgType) -> Res
             check_arg(fname, lhs, larg)?;
             check_arg(fname, rhs, rarg)
         }
-        _ => Err(anyhow!("Invalid argument '{expr}' for function '{fname}'")),
+        _ => Err(err!(
+            Type,
+            "Invalid argument '{expr}' for function '{fname}'"
+        )),
     }
 }
 
@@ -156,7 +245,10 @@ fn check_bool(fname: &str, expr: &Expr) -> Result<()> {
     match expr {
         Expr::Identifier(s) if s == "true" => Ok(()),
         Expr::Identifier(s) if s == "false" => Ok(()),
-        _ => Err(anyhow!("Invalid argument '{expr}' for function '{fname}'")),
+        _ => Err(err!(
+            Type,
+            "Invalid argument '{expr}' for function '{fname}'"
+        )),
     }
 }
 
@@ -171,7 +263,10 @@ fn check_compare(fname: &str, expr: &Expr, larg: &ArgType, rarg: &ArgType) -> Re
             check_arg(fname, lhs, larg)?;
             check_arg(fname, rhs, rarg)
         }
-        _ => Err(anyhow!("Invalid argument '{expr}' for function '{fname}'")),
+        _ => Err(err!(
+            Type,
+            "Invalid argument '{expr}' for function '{fname}'"
+        )),
     }
 }
 
@@ -181,25 +276,72 @@ fn check_equal(fname: &str, expr: &Expr, larg: &ArgType, rarg: &ArgType) -> Resu
             check_arg(fname, lhs, larg)?;
             check_arg(fname, rhs, rarg)
         }
-        _ => Err(anyhow!("Invalid argument '{expr}' for function '{fname}'")),
+        _ => Err(err!(
+            Type,
+            "Invalid argument '{expr}' for function '{fname}'"
+        )),
+    }
+}
+
+fn check_formula(fname: &str, expr: &Expr, larg: &ArgType, rarg: &ArgType) -> Result<()> {
+    match expr {
+        Expr::BinaryOp(lhs, Operator::Formula, rhs) => {
+            check_arg(fname, lhs, larg)?;
+            check_arg(fname, rhs, rarg)
+        }
+        _ => Err(err!(
+            Type,
+            "Invalid argument '{expr}' for function '{fname}'"
+        )),
     }
 }
 
 fn check_function(fname: &str, expr: &Expr, sig_args: &Args) -> Result<()> {
     match expr {
         Expr::Function(name, args) if fname == name => check_args(name, args, sig_args),
-        _ => Err(anyhow!("Invalid argument '{expr}' for function '{fname}'")),
+        _ => Err(err!(
+            Type,
+            "Invalid argument '{expr}' for function '{fname}'"
+        )),
     }
 }
 
 fn check_identifier(fname: &str, expr: &Expr) -> Result<()> {
     if !matches!(expr, Expr::Identifier(_)) {
-        Err(anyhow!("Invalid argument '{expr}' for function '{fname}'"))
+        Err(err!(
+            Type,
+            "Invalid argument '{expr}' for function '{fname}'"
+        ))
     } else {
         Ok(())
     }
 }
 
+fn check_in(fname: &str, expr: &Expr, larg: &ArgType, rarg: &ArgType) -> Result<()> {
+    match expr {
+        Expr::BinaryOp(lhs, Operator::In, rhs) => {
+            check_arg(fname, lhs, larg)?;
+            check_arg(fname, rhs, rarg)
+        }
+        _ => Err(err!(
+            Type,
+            "Invalid argument '{expr}' for function '{fname}'"
+        )),
+    }
+}
+
+fn check_list(fname: &str, expr: &Expr, arg: &ArgType) -> Result<()> {
+    match expr {
+        Expr::List(exprs) => exprs
+            .iter()
+            .try_for_each(|expr| check_arg(fname, expr, arg)),
+        _ => Err(err!(
+            Type,
+            "Invalid argument '{expr}' for function '{fname}'"
+        )),
+    }
+}
+
 fn check_logical(fname: &str, expr: &Expr, arg: &ArgType) -> Result<()> {
     fn is_logical(expr: &Expr) -> bool {
         matches!(
@@ -222,14 +364,20 @@ fn check_logical(fname: &str, expr: &Expr, arg: &ArgType) -> Result<()> {
                 check_arg(fname, rhs, arg)
             }
         }
-        _ => Err(anyhow!("Invalid argument '{expr}' for function '{fname}'")),
+        _ => Err(err!(
+            Type,
+            "Invalid argument '{expr}' for function '{fname}'"
+        )),
     }
 }
 
 fn check_named(fname: &str, name: &str, expr: &Expr) -> Result<()> {
     match expr {
         Expr::Identifier(s) if s == name => Ok(()),
-        _ => Err(anyhow!("Invalid argument '{expr}' for function '{fname}'")),
+        _ => Err(err!(
+            Type,
+            "Invalid argument '{expr}' for function '{fname}'"
+        )),
     }
 }
 
@@ -237,13 +385,19 @@ fn check_negate(fname: &str, expr: &Expr, arg: &ArgType) -> Result<()> {
     if let Expr::UnaryOp(Operator::Not, expr) = expr {
         check_arg(fname, expr, arg)
     } else {
-        Err(anyhow!("Invalid argument '{expr}' for function '{fname}'"))
+        Err(err!(
+            Type,
+            "Invalid argument '{expr}' for function '{fname}'"
+        ))
     }
 }
 
 fn check_number(fname: &str, expr: &Expr) -> Result<()> {
-    if !matches!(expr, Expr::Number(_)) {
-        Err(anyhow!("Invalid argument '{expr}' for function '{fname}'"))
+    if expr.number().is_none() {
+        Err(err!(
+            Type,
+            "Invalid argument '{expr}' for function '{fname}'"
+        ))
     } else {
         Ok(())
     }
@@ -256,12 +410,18 @@ fn check_one_of(fname: &str, expr: &Expr, args: &[ArgType]) -> Result<()> {
         }
     }
 
-    Err(anyhow!("Invalid argument '{expr}' for function '{fname}'"))
+    Err(err!(
+        Type,
+        "Invalid argument '{expr}' for function '{fname}'"
+    ))
 }
 
 fn check_string(fname: &str, expr: &Expr) -> Result<()> {
     if !matches!(expr, Expr::String(_)) {
-        Err(anyhow!("Invalid argument '{expr}' for function '{fname}'"))
+        Err(err!(
+            Type,
+            "Invalid argument '{expr}' for function '{fname}'"
+        ))
     } else {
         Ok(())
     }
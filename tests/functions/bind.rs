@@ -0,0 +1,89 @@
+// Copyright (C) 2023 Vince Vasta
+// SPDX-License-Identifier: Apache-2.0
+use anyhow::Result;
+use indoc::indoc;
+
+use super::assert_interpreter;
+
+#[test]
+fn bind_rows() -> Result<()> {
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            select(payment_type, total_amount) |
+            slice(1, 2) |
+            first_df
+
+        parquet("tests/data/nyctaxi.parquet") |
+            select(tip_amount, payment_type) |
+            slice(3, 2) |
+            second_df
+
+        first_df | bind_rows(second_df) | show()
+        first_df | bind_rows(second_df, first_df, id = source) | show()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (4, 3)
+            payment_type|total_amount|tip_amount
+            str|f64|f64
+            ---
+            Credit card|22.56|null
+            Cash|9.8|null
+            Credit card|null|2.96
+            Credit card|null|4.36
+            ---
+            shape: (6, 4)
+            source|payment_type|total_amount|tip_amount
+            u32|str|f64|f64
+            ---
+            1|Credit card|22.56|null
+            1|Cash|9.8|null
+            2|Credit card|null|2.96
+            2|Credit card|null|4.36
+            3|Credit card|22.56|null
+            3|Cash|9.8|null
+            ---
+       "#
+        )
+    );
+
+    Ok(())
+}
+
+#[test]
+fn bind_cols() -> Result<()> {
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            select(payment_type) |
+            slice(1, 3) |
+            types_df
+
+        parquet("tests/data/nyctaxi.parquet") |
+            select(total_amount) |
+            slice(1, 3) |
+            amounts_df
+
+        types_df | bind_cols(amounts_df) | show()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (3, 2)
+            payment_type|total_amount
+            str|f64
+            ---
+            Credit card|22.56
+            Cash|9.8
+            Credit card|17.76
+            ---
+       "#
+        )
+    );
+
+    Ok(())
+}
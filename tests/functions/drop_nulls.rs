@@ -0,0 +1,74 @@
+// Copyright (C) 2023 Vince Vasta
+// SPDX-License-Identifier: Apache-2.0
+use anyhow::Result;
+use indoc::indoc;
+
+use super::assert_interpreter;
+
+#[test]
+fn drop_nulls() -> Result<()> {
+    let input = indoc! {r#"
+        parquet("tests/data/lists.parquet") |
+            drop_nulls(ints, floats) |
+            count() |
+            show()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (1, 1)
+            n
+            u32
+            ---
+            150
+            ---
+       "#
+        )
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/lists.parquet") |
+            drop_nulls() |
+            count() |
+            show()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (1, 1)
+            n
+            u32
+            ---
+            120
+            ---
+       "#
+        )
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/lists.parquet") |
+            filter(!is_null(ints) & !is_null(floats) & !is_null(tags)) |
+            count() |
+            show()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (1, 1)
+            n
+            u32
+            ---
+            120
+            ---
+       "#
+        )
+    );
+
+    Ok(())
+}
@@ -559,6 +559,35 @@ fn filter_list_not_contains() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn filter_list_contains_literal() -> Result<()> {
+    let input = indoc! {r#"
+        parquet("tests/data/lists.parquet") |
+          filter(contains(tags, "tag7", literal = true), !contains(tags, "tag2", literal = true)) |
+          select(tags) |
+          head(4)
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (4, 1)
+            tags
+            list[str]
+            ---
+            ["tag7"]
+            ["tag5", "tag6", "tag7", "tag7"]
+            ["tag4", "tag7", "tag8"]
+            ["tag7"]
+            ---
+       "#
+        )
+    );
+
+    Ok(())
+}
+
 #[test]
 fn filter_str_contains() -> Result<()> {
     // Detect payment types that contain 'no' ignoring case
@@ -618,6 +647,76 @@ fn filter_str_not_contains() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn filter_str_predicates() -> Result<()> {
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            filter(starts_with(payment_type, "Cr") | ends_with(payment_type, "ute")) |
+            distinct(payment_type) |
+            arrange(payment_type) |
+            show()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (2, 1)
+            payment_type
+            str
+            ---
+            Credit card
+            Dispute
+            ---
+       "#
+        )
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            filter(matches(payment_type, "^no ", ignore_case = true)) |
+            distinct(payment_type) |
+            show()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (1, 1)
+            payment_type
+            str
+            ---
+            No charge
+            ---
+       "#
+        )
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            filter(str_equals_ci(payment_type, "CASH"), contains(payment_type, "as", literal = true)) |
+            distinct(payment_type) |
+            show()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (1, 1)
+            payment_type
+            str
+            ---
+            Cash
+            ---
+       "#
+        )
+    );
+
+    Ok(())
+}
+
 #[test]
 fn filter_is_null() -> Result<()> {
     // Detect payment types that contain 'no' ignoring case
@@ -689,3 +788,219 @@ fn filter_is_not_null() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn filter_is_in() -> Result<()> {
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            filter(payment_type %in% ("Cash", "Dispute")) |
+            count(payment_type) |
+            arrange(payment_type) |
+            show()
+        parquet("tests/data/nyctaxi.parquet") |
+            filter(is_in(passenger_count, [1, 5])) |
+            count(passenger_count) |
+            arrange(passenger_count) |
+            show()
+        parquet("tests/data/nyctaxi.parquet") |
+            filter(!(passenger_count %in% [1, 2, 3])) |
+            count(passenger_count) |
+            arrange(passenger_count) |
+            show()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (2, 2)
+            payment_type|n
+            str|u32
+            ---
+            Cash|53
+            Dispute|2
+            ---
+            shape: (2, 2)
+            passenger_count|n
+            i64|u32
+            ---
+            1|182
+            5|10
+            ---
+            shape: (4, 2)
+            passenger_count|n
+            i64|u32
+            ---
+            0|1
+            4|6
+            5|10
+            6|1
+            ---
+       "#
+        )
+    );
+
+    Ok(())
+}
+
+#[test]
+fn filter_is_in_dataframe() -> Result<()> {
+    let input = indoc! {r#"
+        csv("tests/data/zones.csv") | filter(Borough == "Queens") | zones_df
+        parquet("tests/data/nyctaxi.parquet") |
+            filter(PULocationID %in% zones_df.LocationID) |
+            distinct(PULocationID) |
+            arrange(PULocationID) |
+            show()
+        parquet("tests/data/nyctaxi.parquet") |
+            group_by(payment_type) |
+            filter(!is_in(PULocationID, zones_df.LocationID) & total_amount > 60) |
+            summarize(n = n()) |
+            arrange(payment_type) |
+            show()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (5, 1)
+            PULocationID
+            i64
+            ---
+            56
+            70
+            132
+            138
+            215
+            ---
+            shape: (2, 2)
+            payment_type|n
+            str|u32
+            ---
+            Cash|2
+            Credit card|6
+            ---
+       "#
+        )
+    );
+
+    Ok(())
+}
+
+#[test]
+fn filter_between() -> Result<()> {
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            filter(between(total_amount, 10, 10.5)) |
+            select(total_amount) |
+            show()
+        parquet("tests/data/nyctaxi.parquet") |
+            filter(between(tpep_pickup_datetime, dt("2022-11-30 20:00:00"), dt("2022-11-30 23:59:59"))) |
+            select(tpep_pickup_datetime) |
+            show()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (3, 1)
+            total_amount
+            f64
+            ---
+            10.3
+            10.3
+            10.38
+            ---
+            shape: (2, 1)
+            tpep_pickup_datetime
+            datetime[ns]
+            ---
+            2022-11-30 22:24:08
+            2022-11-30 23:44:02
+            ---
+       "#
+        )
+    );
+
+    Ok(())
+}
+
+#[test]
+fn filter_expressions() -> Result<()> {
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            filter(total_amount * 2 > 100 & len(payment_type) > 8) |
+            select(total_amount, payment_type) |
+            head(4)
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (4, 2)
+            total_amount|payment_type
+            f64|str
+            ---
+            56.09|Credit card
+            77.6|Credit card
+            53.21|Credit card
+            56.06|Credit card
+            ---
+       "#
+        )
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            filter(round(tip_amount) == 2, passenger_count + 1 >= 4) |
+            select(tip_amount, passenger_count) |
+            head(4)
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (4, 2)
+            tip_amount|passenger_count
+            f64|i64
+            ---
+            1.76|5
+            1.5|3
+            1.96|5
+            1.96|5
+            ---
+       "#
+        )
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            group_by(payment_type) |
+            filter(fare_amount > mean(fare_amount) * 3) |
+            summarize(n = n()) |
+            arrange(payment_type) |
+            show()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (3, 2)
+            payment_type|n
+            str|u32
+            ---
+            Cash|3
+            Credit card|9
+            Dispute|1
+            ---
+       "#
+        )
+    );
+
+    Ok(())
+}
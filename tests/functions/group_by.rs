@@ -285,3 +285,394 @@ fn summarize_list() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn group_by_distinct_mode() -> Result<()> {
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            group_by(payment_type) |
+            summarize(
+                passengers = n_distinct(passenger_count),
+                approx = approx_n_distinct(passenger_count),
+                nulls = null_count(passenger_count),
+                common = mode(passenger_count),
+                n = n()
+            ) |
+            arrange(desc(n)) |
+            show()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (5, 6)
+            payment_type|passengers|approx|nulls|common|n
+            str|u32|u32|u32|i64|u32
+            ---
+            Credit card|6|6|0|1|185
+            Cash|6|6|0|1|53
+            Unknown|1|1|9|null|9
+            Dispute|2|2|0|1|2
+            No charge|1|1|0|1|1
+            ---
+       "#
+        )
+    );
+
+    Ok(())
+}
+
+#[test]
+fn group_by_first_last_nth() -> Result<()> {
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            group_by(payment_type) |
+            summarize(
+                first = first(trip_distance),
+                second = nth(trip_distance, 2),
+                before_last = nth(trip_distance, -2),
+                last = last(trip_distance),
+                n = n()
+            ) |
+            arrange(desc(n)) |
+            show()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (5, 6)
+            payment_type|first|second|before_last|last|n
+            str|f64|f64|f64|f64|u32
+            ---
+            Credit card|3.14|2.36|2.32|1.8|185
+            Cash|1.06|2.39|2.24|2.5|53
+            Unknown|0.0|4.28|0.0|2.1|9
+            Dispute|0.43|0.8|0.43|0.8|2
+            No charge|1.1|null|null|1.1|1
+            ---
+       "#
+        )
+    );
+
+    Ok(())
+}
+
+#[test]
+fn group_by_any_all_cor() -> Result<()> {
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            mutate(tipped = as_bool(tip_amount)) |
+            group_by(payment_type) |
+            summarize(
+                any_tip = any(tipped),
+                all_tip = all(tipped),
+                fare_mile = weighted_mean(fare_amount, trip_distance),
+                skew = skew(fare_amount),
+                kurtosis = kurtosis(fare_amount),
+                cor = cor(fare_amount, trip_distance),
+                n = n()
+            ) |
+            arrange(desc(n)) |
+            show()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (5, 8)
+            payment_type|any_tip|all_tip|fare_mile|skew|kurtosis|cor|n
+            str|bool|bool|f64|f64|f64|f64|u32
+            ---
+            Credit card|true|false|27.800002|2.04824|4.013147|0.896703|185
+            Cash|false|false|26.641576|2.270218|4.926924|0.975655|53
+            Unknown|true|false|29.070467|0.327528|-0.708127|0.875589|9
+            Dispute|false|false|-1.528455|0.0|-2.0|-1.0|2
+            No charge|false|false|5.5|NaN|NaN|NaN|1
+            ---
+       "#
+        )
+    );
+
+    Ok(())
+}
+
+#[test]
+fn summarize_str_join() -> Result<()> {
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            distinct(payment_type) |
+            arrange(payment_type) |
+            summarize(types = str_join(payment_type, ", "), n = n_distinct(payment_type)) |
+            show()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (1, 2)
+            types|n
+            str|u32
+            ---
+            Cash, Credit card, Dispute, No charge, Unknown|5
+            ---
+       "#
+        )
+    );
+
+    Ok(())
+}
+
+#[test]
+fn group_by_expressions() -> Result<()> {
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            group_by(payment_type) |
+            summarize(
+                revenue = sum(fare_amount * passenger_count),
+                long_share = mean(trip_distance > 5),
+                big = sum(if_else(total_amount > 30, 1, 0)),
+                tip_pct = sum(tip_amount) / sum(total_amount) * 100,
+                n = n()
+            ) |
+            arrange(desc(n)) |
+            show()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (5, 6)
+            payment_type|revenue|long_share|big|tip_pct|n
+            str|f64|f64|f64|f64|u32
+            ---
+            Credit card|3848.5|0.194595|35.0|15.50481|185
+            Cash|1218.0|0.150943|4.0|0.0|53
+            Unknown|0.0|0.222222|2.0|11.480363|9
+            Dispute|-5.0|0.0|0.0|-0.0|2
+            No charge|5.5|0.0|0.0|0.0|1
+            ---
+       "#
+        )
+    );
+
+    Ok(())
+}
+
+#[test]
+fn summarize_expressions() -> Result<()> {
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            summarize(
+                fare_range = max(fare_amount) - min(fare_amount),
+                letters = sum(len(payment_type)),
+                per_trip = sum(total_amount) / n()
+            ) |
+            show()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (1, 3)
+            fare_range|letters|per_trip
+            f64|u32|f64
+            ---
+            74.5|2333|21.4712
+            ---
+       "#
+        )
+    );
+
+    Ok(())
+}
+
+#[test]
+fn group_by_mutate_filter() -> Result<()> {
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            select(payment_type, total_amount) |
+            group_by(payment_type) |
+            mutate(
+                share = round(total_amount / sum(total_amount) * 100, 2),
+                trip = row_number(),
+                previous = lag(total_amount)
+            ) |
+            ungroup() |
+            head(6)
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (6, 5)
+            payment_type|total_amount|share|trip|previous
+            str|f64|f64|u32|f64
+            ---
+            Credit card|22.56|0.54|1|null
+            Cash|9.8|1.0|1|null
+            Credit card|17.76|0.43|2|22.56
+            Credit card|26.16|0.63|3|17.76
+            Credit card|19.55|0.47|4|26.16
+            Cash|22.3|2.28|2|9.8
+            ---
+       "#
+        )
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            select(payment_type, passenger_count, total_amount) |
+            group_by(payment_type) |
+            filter(total_amount == max(total_amount)) |
+            ungroup() |
+            arrange(payment_type) |
+            show()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (5, 3)
+            payment_type|passenger_count|total_amount
+            str|i64|f64
+            ---
+            Cash|1|63.1
+            Credit card|1|84.36
+            Dispute|1|7.3
+            No charge|1|8.8
+            Unknown|null|54.47
+            ---
+       "#
+        )
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            group_by(payment_type) |
+            filter(total_amount > mean(total_amount)) |
+            mutate(rounded = round(total_amount)) |
+            summarize(n = n(), total = sum(rounded)) |
+            arrange(payment_type) |
+            show()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (4, 3)
+            payment_type|n|total
+            str|u32|f64
+            ---
+            Cash|17|526.0
+            Credit card|52|2197.0
+            Dispute|1|7.0
+            Unknown|3|128.0
+            ---
+       "#
+        )
+    );
+
+    Ok(())
+}
+
+#[test]
+fn group_by_show_select() -> Result<()> {
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            select(payment_type, total_amount) |
+            group_by(payment_type) |
+            mutate(share = round(total_amount / sum(total_amount) * 100, 2)) |
+            head(4)
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (4, 3)
+            payment_type|total_amount|share
+            str|f64|f64
+            ---
+            Credit card|22.56|0.54
+            Cash|9.8|1.0
+            Credit card|17.76|0.43
+            Credit card|26.16|0.63
+            ---
+       "#
+        )
+    );
+
+    // Group columns are kept by select.
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            group_by(payment_type) |
+            slice_max(total_amount, 2) |
+            select(total_amount) |
+            arrange(payment_type) |
+            show()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (10, 2)
+            payment_type|total_amount
+            str|f64
+            ---
+            Cash|63.1
+            Cash|61.85
+            Cash|61.85
+            Credit card|84.36
+            Credit card|77.64
+            Dispute|7.3
+            Dispute|-8.3
+            No charge|8.8
+            Unknown|54.47
+            Unknown|45.5
+            ---
+       "#
+        )
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            group_by(payment_type) |
+            slice_max(total_amount, 2) |
+            select(total_amount) |
+            rename(type = payment_type) |
+            summarize(n = n(), max = max(total_amount)) |
+            arrange(type) |
+            show()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (5, 3)
+            type|n|max
+            str|u32|f64
+            ---
+            Cash|3|63.1
+            Credit card|2|84.36
+            Dispute|2|7.3
+            No charge|1|8.8
+            Unknown|2|54.47
+            ---
+       "#
+        )
+    );
+
+    Ok(())
+}
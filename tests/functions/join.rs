@@ -308,3 +308,305 @@ fn anti_join() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn semi_join() -> Result<()> {
+    let input = indoc! {r#"
+        parquet("tests/data/lists.parquet") |
+            select(shape_id) |
+            mutate(right_val = shape_id * 2) |
+            filter(shape_id > 5) |
+            right_df
+
+        parquet("tests/data/lists.parquet") |
+            select(shape_id) |
+            filter(shape_id < 8) |
+            mutate(left_val = shape_id * 2) |
+            semi_join(right_df) |
+            arrange(shape_id) |
+            head()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (2, 2)
+            shape_id|left_val
+            u32|f64
+            ---
+            6|12.0
+            7|14.0
+            ---
+       "#
+        )
+    );
+
+    Ok(())
+}
+
+#[test]
+fn join_suffix_validate() -> Result<()> {
+    let input = indoc! {r#"
+        parquet("tests/data/lists.parquet") |
+            select(shape_id) |
+            mutate(val = shape_id * 2) |
+            filter(shape_id > 5) |
+            right_df
+
+        parquet("tests/data/lists.parquet") |
+            select(shape_id) |
+            filter(shape_id < 8) |
+            mutate(val = shape_id * 3) |
+            inner_join(right_df, shape_id == shape_id, suffix = "_rhs", validate = "1:1") |
+            arrange(shape_id) |
+            head()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (2, 3)
+            shape_id|val|val_rhs
+            u32|f64|f64
+            ---
+            6|18.0|12.0
+            7|21.0|14.0
+            ---
+       "#
+        )
+    );
+
+    Ok(())
+}
+
+#[test]
+fn asof_join() -> Result<()> {
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            select(VendorID, pickup = tpep_pickup_datetime, payment_type) |
+            slice(1, 5) |
+            trips_df
+        parquet("tests/data/nyctaxi.parquet") |
+            select(VendorID, dropoff = tpep_dropoff_datetime, total_amount) |
+            slice(6, 30) |
+            dropoffs_df
+        trips_df |
+            asof_join(dropoffs_df, pickup == dropoff, by = VendorID) |
+            show()
+        trips_df |
+            asof_join(dropoffs_df, pickup == dropoff, by = VendorID, tolerance = "1d") |
+            show()
+        trips_df |
+            asof_join(dropoffs_df, pickup == dropoff, strategy = "forward", suffix = "_dropoff") |
+            show()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (5, 5)
+            VendorID|pickup|payment_type|dropoff|total_amount
+            i64|datetime[ns]|str|datetime[ns]|f64
+            ---
+            2|2022-11-22 19:27:01|Credit card|2022-11-22 14:46:38|11.8
+            2|2022-11-27 16:43:26|Cash|2022-11-23 09:18:11|9.13
+            2|2022-11-12 16:58:37|Credit card|2022-11-10 15:56:28|10.56
+            2|2022-11-30 22:24:08|Credit card|2022-11-30 15:17:39|22.3
+            1|2022-11-26 23:03:41|Credit card|2022-11-25 20:33:16|17.28
+            ---
+            shape: (5, 5)
+            VendorID|pickup|payment_type|dropoff|total_amount
+            i64|datetime[ns]|str|datetime[ns]|f64
+            ---
+            2|2022-11-22 19:27:01|Credit card|2022-11-22 14:46:38|11.8
+            2|2022-11-27 16:43:26|Cash|null|null
+            2|2022-11-12 16:58:37|Credit card|null|null
+            2|2022-11-30 22:24:08|Credit card|2022-11-30 15:17:39|22.3
+            1|2022-11-26 23:03:41|Credit card|null|null
+            ---
+            shape: (5, 6)
+            VendorID|pickup|payment_type|dropoff|VendorID_dropoff|total_amount
+            i64|datetime[ns]|str|datetime[ns]|i64|f64
+            ---
+            2|2022-11-22 19:27:01|Credit card|2022-11-22 19:36:01|2|16.3
+            2|2022-11-27 16:43:26|Cash|2022-11-28 10:02:07|2|11.3
+            2|2022-11-12 16:58:37|Credit card|2022-11-13 14:22:43|2|12.96
+            2|2022-11-30 22:24:08|Credit card|null|null|null
+            1|2022-11-26 23:03:41|Credit card|2022-11-27 03:02:13|1|10.8
+            ---
+       "#
+        )
+    );
+
+    Ok(())
+}
+
+#[test]
+fn asof_join_integer_key() -> Result<()> {
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            select(PULocationID, payment_type) |
+            slice(1, 5) |
+            trips_df
+        parquet("tests/data/nyctaxi.parquet") |
+            select(LocationID = DOLocationID, total_amount) |
+            slice(20, 10) |
+            zones_df
+        trips_df |
+            asof_join(zones_df, PULocationID == LocationID, tolerance = 10) |
+            show()
+        trips_df |
+            asof_join(zones_df, PULocationID == LocationID, strategy = "nearest", tolerance = 2) |
+            show()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (5, 4)
+            PULocationID|payment_type|LocationID|total_amount
+            i64|str|i64|f64
+            ---
+            234|Credit card|233|15.3
+            48|Cash|48|7.3
+            142|Credit card|140|12.96
+            79|Credit card|null|null
+            237|Credit card|233|15.3
+            ---
+            shape: (5, 4)
+            PULocationID|payment_type|LocationID|total_amount
+            i64|str|i64|f64
+            ---
+            234|Credit card|233|15.3
+            48|Cash|48|7.3
+            142|Credit card|140|12.96
+            79|Credit card|null|null
+            237|Credit card|null|null
+            ---
+       "#
+        )
+    );
+
+    Ok(())
+}
+
+#[test]
+fn range_join() -> Result<()> {
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            select(VendorID, total_amount) |
+            slice(1, 5) |
+            trips_df
+        parquet("tests/data/nyctaxi.parquet") |
+            select(VendorID, lo = fare_amount, hi = total_amount) |
+            slice(10, 6) |
+            ranges_df
+        trips_df |
+            inner_join(ranges_df, total_amount >= lo, total_amount < hi) |
+            show()
+        trips_df |
+            left_join(ranges_df, VendorID == VendorID, total_amount > hi, suffix = "_range") |
+            show()
+        trips_df |
+            anti_join(ranges_df, total_amount >= lo, total_amount < hi) |
+            show()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (3, 5)
+            VendorID|total_amount|VendorID_right|lo|hi
+            i64|f64|i64|f64|f64
+            ---
+            2|9.8|2|9.0|15.36
+            2|9.8|2|5.5|10.56
+            2|17.76|1|11.5|18.5
+            ---
+            shape: (12, 4)
+            VendorID|total_amount|lo|hi
+            i64|f64|f64|f64
+            ---
+            2|22.56|9.0|15.36
+            2|22.56|5.5|10.56
+            2|22.56|11.5|15.8
+            2|9.8|null|null
+            2|17.76|9.0|15.36
+            2|17.76|5.5|10.56
+            2|17.76|11.5|15.8
+            2|26.16|9.0|15.36
+            2|26.16|5.5|10.56
+            2|26.16|11.5|15.8
+            1|19.55|11.0|15.3
+            1|19.55|11.5|18.5
+            ---
+            shape: (3, 2)
+            VendorID|total_amount
+            i64|f64
+            ---
+            2|22.56
+            2|26.16
+            1|19.55
+            ---
+       "#
+        )
+    );
+
+    Ok(())
+}
+
+#[test]
+fn range_join_float_keys() -> Result<()> {
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            select(tip_amount) |
+            slice(1, 4) |
+            mutate(delta = tip_amount - 3, zero = tip_amount * -0.0) |
+            tips_df
+        parquet("tests/data/nyctaxi.parquet") |
+            select(extra) |
+            slice(1, 3) |
+            mutate(lo = extra - 1, hi = extra + 1, zero = extra * 0) |
+            bounds_df
+        tips_df |
+            inner_join(bounds_df, delta >= lo, delta < hi) |
+            select(delta, lo, hi) |
+            show()
+        tips_df |
+            inner_join(bounds_df, zero >= zero, zero <= zero) |
+            count() |
+            show()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (6, 3)
+            delta|lo|hi
+            f64|f64|f64
+            ---
+            0.76|0.0|2.0
+            0.76|-1.0|1.0
+            0.76|-1.0|1.0
+            -0.04|-1.0|1.0
+            -0.04|-1.0|1.0
+            1.36|0.0|2.0
+            ---
+            shape: (1, 1)
+            n
+            u32
+            ---
+            12
+            ---
+       "#
+        )
+    );
+
+    Ok(())
+}
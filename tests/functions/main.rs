@@ -3,9 +3,11 @@
 
 //! Test binary for all dply functions.
 mod arrange;
+mod bind;
 mod count;
 mod df_var;
 mod distinct;
+mod drop_nulls;
 mod filter;
 mod glimpse;
 mod group_by;
@@ -13,10 +15,15 @@ mod head;
 mod join;
 mod json;
 mod mutate;
+mod pivot;
 mod relocate;
 mod rename;
 mod select;
+mod separate;
+mod setops;
 mod show;
+mod slice;
+mod typing;
 mod unnest;
 
 macro_rules! assert_interpreter {
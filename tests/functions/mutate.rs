@@ -83,6 +83,40 @@ fn mutate_arith() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn mutate_unary_minus() -> Result<()> {
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            select(passenger_count, trip_distance) |
+            mutate(
+                a = -2 ^ 2,
+                b = -trip_distance,
+                c = 2 * -passenger_count,
+                d = -(trip_distance ^ 2),
+                e = -trip_distance ^ 2 + 1
+            ) |
+            head(3)
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (3, 7)
+            passenger_count|trip_distance|a|b|c|d|e
+            i64|f64|f64|f64|f64|f64|f64
+            ---
+            1|3.14|-4.0|-3.14|-2.0|-9.8596|-8.8596
+            2|1.06|-4.0|-1.06|-4.0|-1.1236|-0.1236
+            1|2.36|-4.0|-2.36|-2.0|-5.5696|-4.5696
+            ---
+       "#
+        )
+    );
+
+    Ok(())
+}
+
 #[test]
 fn mutate_mean() -> Result<()> {
     let input = indoc! {r#"
@@ -493,3 +527,523 @@ fn mutate_durations() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn mutate_conditionals() -> Result<()> {
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            select(passenger_count, payment_type, total_amount) |
+            mutate(
+                cash_tip = if_else(payment_type == "Cash", total_amount * 0.1, 0),
+                group = case_when(
+                    passenger_count < 2 ~ "single",
+                    passenger_count < 4 & total_amount < 20 ~ "small",
+                    true ~ "large"
+                ),
+                cash = case_when(payment_type == "Cash" ~ total_amount)
+            ) |
+            filter(passenger_count > 1) |
+            head(6)
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (6, 6)
+            passenger_count|payment_type|total_amount|cash_tip|group|cash
+            i64|str|f64|f64|str|f64
+            ---
+            2|Cash|9.8|0.98|small|9.8
+            3|Credit card|19.55|0.0|small|null
+            2|Cash|11.8|1.18|small|11.8
+            2|Credit card|15.36|0.0|small|null
+            2|Credit card|10.56|0.0|small|null
+            5|Credit card|9.13|0.0|large|null
+            ---
+       "#
+        )
+    );
+
+    Ok(())
+}
+
+#[test]
+fn mutate_strings() -> Result<()> {
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            distinct(payment_type) |
+            mutate(
+                upper = to_upper(payment_type),
+                lower = to_lower(payment_type),
+                code = substr(upper, 1, 4),
+                padded = str_pad(code, 6, side = "right", pad = "_"),
+                words = split(payment_type, " "),
+                label = concat(code, "-", len(payment_type))
+            ) |
+            arrange(payment_type) |
+            show()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (5, 7)
+            payment_type|upper|lower|code|padded|words|label
+            str|str|str|str|str|list[str]|str
+            ---
+            Cash|CASH|cash|CASH|CASH__|["Cash"]|CASH-4
+            Credit card|CREDIT CARD|credit card|CRED|CRED__|["Credit", "card"]|CRED-11
+            Dispute|DISPUTE|dispute|DISP|DISP__|["Dispute"]|DISP-7
+            No charge|NO CHARGE|no charge|NO C|NO C__|["No", "charge"]|NO C-9
+            Unknown|UNKNOWN|unknown|UNKN|UNKN__|["Unknown"]|UNKN-7
+            ---
+       "#
+        )
+    );
+
+    let input = indoc! {r#"
+        csv("tests/data/zones.csv") |
+            mutate(
+                zone = trim(concat("  ", Zone, " ")),
+                short = replace(Borough, "an", "AN"),
+                all = replace_all(Borough, "an", "AN"),
+                first = extract(service_zone, "^(\w+)", 1),
+                consonants = replace_all(Borough, "[aeiou]", "")
+            ) |
+            select(zone, short, all, first, consonants) |
+            head(5)
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (5, 5)
+            zone|short|all|first|consonants
+            str|str|str|str|str
+            ---
+            Newark Airport|EWR|EWR|EWR|EWR
+            Jamaica Bay|Queens|Queens|Boro|Qns
+            Allerton/Pelham Gardens|Bronx|Bronx|Boro|Brnx
+            Alphabet City|MANhattan|MANhattAN|Yellow|Mnhttn
+            Arden Heights|Staten IslANd|Staten IslANd|Boro|Sttn Islnd
+            ---
+       "#
+        )
+    );
+
+    Ok(())
+}
+
+#[test]
+fn mutate_math() -> Result<()> {
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            mutate(
+                z = round((total_amount - mean(total_amount)) / sd(total_amount), 2),
+                sq = trip_distance ^ 2,
+                root = round(sqrt(trip_distance), 3),
+                lg = round(log10(total_amount), 3),
+                tip = abs(floor(tip_amount) - ceil(fare_amount)),
+                clipped = clip(fare_amount, 5, 15),
+                prec = 1 + 2 * 3 ^ 2
+            ) |
+            select(z, sq, root, lg, tip, clipped, prec) |
+            head(5)
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (5, 7)
+            z|sq|root|lg|tip|clipped|prec
+            f64|f64|f64|f64|f64|f64|f64
+            ---
+            0.07|9.8596|1.772|1.353|12.0|14.5|19.0
+            -0.75|1.1236|1.03|0.991|7.0|6.5|19.0
+            -0.24|5.5696|1.536|1.249|10.0|11.5|19.0
+            0.3|27.04|2.28|1.418|14.0|15.0|19.0
+            -0.12|0.0|0.0|1.291|10.0|12.5|19.0
+            ---
+       "#
+        )
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            mutate(
+                share = round(tip_amount / sum(tip_amount) * 100, 4),
+                e = round(exp(1), 4),
+                ln = round(log(total_amount), 2),
+                l2 = log2(passenger_count),
+                s = sign(tip_amount - 2),
+                p = pow(2, passenger_count),
+                trig = round(sin(0) + cos(0) + atan(1) * 4, 4),
+                v = round(var(trip_distance), 2)
+            ) |
+            select(share, e, ln, l2, s, p, trig, v) |
+            head(5)
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (5, 8)
+            share|e|ln|l2|s|p|trig|v
+            f64|f64|f64|f64|i64|f64|f64|f64
+            ---
+            0.5615|2.7183|3.12|0.0|1|2.0|4.1416|16.76
+            0.0|2.7183|2.28|1.0|-1|4.0|4.1416|16.76
+            0.442|2.7183|2.88|0.0|1|2.0|4.1416|16.76
+            0.6511|2.7183|3.26|0.0|1|2.0|4.1416|16.76
+            0.4853|2.7183|2.97|1.584963|1|8.0|4.1416|16.76
+            ---
+       "#
+        )
+    );
+
+    Ok(())
+}
+
+#[test]
+fn mutate_dates() -> Result<()> {
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            select(ts = tpep_pickup_datetime) |
+            mutate(
+                year = year(ts),
+                quarter = quarter(ts),
+                month = month(ts),
+                day = day(ts),
+                hour = hour(ts),
+                minute = minute(ts),
+                second = second(ts),
+                weekday = weekday(ts),
+                week = week(ts),
+                doy = day_of_year(ts)
+            ) |
+            head(3)
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (3, 11)
+            ts|year|quarter|month|day|hour|minute|second|weekday|week|doy
+            datetime[ns]|i32|i8|i8|i8|i8|i8|i8|i8|i8|i16
+            ---
+            2022-11-22 19:27:01|2022|4|11|22|19|27|1|2|47|326
+            2022-11-27 16:43:26|2022|4|11|27|16|43|26|7|47|331
+            2022-11-12 16:58:37|2022|4|11|12|16|58|37|6|45|316
+            ---
+       "#
+        )
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            select(start = tpep_pickup_datetime, end = tpep_dropoff_datetime) |
+            mutate(
+                hour = floor_date(start, "1h"),
+                quarter = ceil_date(start, "15m"),
+                text = strftime(start, "%d/%m/%Y %H:%M"),
+                minutes = date_diff(start, end, "minutes"),
+                seconds = date_diff(start, end, "seconds")
+            ) |
+            select(hour, quarter, text, minutes, seconds) |
+            head(3)
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (3, 5)
+            hour|quarter|text|minutes|seconds
+            datetime[ns]|datetime[ns]|str|i64|i64
+            ---
+            2022-11-22 19:00:00|2022-11-22 19:30:00|22/11/2022 19:27|18|1132
+            2022-11-27 16:00:00|2022-11-27 16:45:00|27/11/2022 16:43|6|400
+            2022-11-12 16:00:00|2022-11-12 17:00:00|12/11/2022 16:58|13|834
+            ---
+       "#
+        )
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            select(ts = tpep_pickup_datetime) |
+            mutate(
+                iso = ymd(strftime(ts, "%Y/%m/%d")),
+                eu = dmy(strftime(ts, "%d.%m.%Y")),
+                us = mdy(strftime(ts, "%m-%d-%Y")),
+                invalid = ymd(strftime(ts, "%m-%d-%Y"))
+            ) |
+            head(3)
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (3, 5)
+            ts|iso|eu|us|invalid
+            datetime[ns]|date|date|date|date
+            ---
+            2022-11-22 19:27:01|2022-11-22|2022-11-22|2022-11-22|null
+            2022-11-27 16:43:26|2022-11-27|2022-11-27|2022-11-27|null
+            2022-11-12 16:58:37|2022-11-12|2022-11-12|2022-11-12|null
+            ---
+       "#
+        )
+    );
+
+    Ok(())
+}
+
+#[test]
+fn mutate_casts() -> Result<()> {
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            mutate(
+                count_str = as_str(passenger_count),
+                count_int = as_int(count_str),
+                count_float = as_float(count_str),
+                flag = as_int(store_and_fwd_flag, strict = false),
+                many = as_bool(if_else(passenger_count > 1, "TRUE", "false")),
+                has_tip = as_bool(tip_amount),
+                payment = as_category(payment_type)
+            ) |
+            select(count_str, count_int, count_float, flag, many, has_tip, payment) |
+            head(4)
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (4, 7)
+            count_str|count_int|count_float|flag|many|has_tip|payment
+            str|i64|f64|i64|bool|bool|cat
+            ---
+            1|1|1.0|null|false|true|Credit card
+            2|2|2.0|null|true|false|Cash
+            1|1|1.0|null|false|true|Credit card
+            1|1|1.0|null|false|true|Credit card
+            ---
+       "#
+        )
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            mutate(
+                day = as_date(concat("0", as_str(passenger_count), "/05/2023"), "%d/%m/%Y"),
+                bad_day = as_date(payment_type, "%d/%m/%Y", strict = false),
+                pickup_day = as_date(tpep_pickup_datetime),
+                rome = as_datetime("2023-05-01 10:30", "%Y-%m-%d %H:%M", "Europe/Rome"),
+                utc = as_datetime(concat("2023-05-01 1", as_str(passenger_count), ":00:00"))
+            ) |
+            select(day, bad_day, pickup_day, rome, utc) |
+            head(4)
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (4, 5)
+            day|bad_day|pickup_day|rome|utc
+            date|date|date|datetime[ns, Europe/Rome]|datetime[ns]
+            ---
+            2023-05-01|null|2022-11-22|2023-05-01 10:30:00 CEST|2023-05-01 11:00:00
+            2023-05-02|null|2022-11-27|2023-05-01 10:30:00 CEST|2023-05-01 12:00:00
+            2023-05-01|null|2022-11-12|2023-05-01 10:30:00 CEST|2023-05-01 11:00:00
+            2023-05-01|null|2022-11-30|2023-05-01 10:30:00 CEST|2023-05-01 11:00:00
+            ---
+       "#
+        )
+    );
+
+    Ok(())
+}
+
+#[test]
+fn mutate_nulls() -> Result<()> {
+    let input = indoc! {r#"
+        parquet("tests/data/lists.parquet") |
+            mutate(
+                a = as_int(replace(as_str(shape_id), "[136]", "x"), strict = false),
+                b = as_int(replace(as_str(shape_id), "[37]", "x"), strict = false),
+                first = coalesce(a, b, 0),
+                zero = fill_null(a, 0),
+                forward = fill_null(a, "forward"),
+                backward = fill_null(a, "backward"),
+                mean = fill_null(a, "mean")
+            ) |
+            select(shape_id, a, b, first, zero, forward, backward, mean) |
+            head(8)
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (8, 8)
+            shape_id|a|b|first|zero|forward|backward|mean
+            u32|i64|i64|f64|f64|i64|i64|f64
+            ---
+            1|null|1|1.0|0.0|null|2|109.571429
+            2|2|2|2.0|2.0|2|2|2.0
+            3|null|null|0.0|0.0|2|4|109.571429
+            4|4|4|4.0|4.0|4|4|4.0
+            5|5|5|5.0|5.0|5|5|5.0
+            6|null|6|6.0|0.0|5|7|109.571429
+            7|7|null|7.0|7.0|7|7|7.0
+            8|8|8|8.0|8.0|8|8|8.0
+            ---
+       "#
+        )
+    );
+
+    Ok(())
+}
+
+#[test]
+fn mutate_windows() -> Result<()> {
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            select(tpep_pickup_datetime, passenger_count, total_amount) |
+            arrange(tpep_pickup_datetime) |
+            mutate(
+                row = row_number(),
+                prev = lag(total_amount),
+                next2 = lead(total_amount, 2),
+                running = cumsum(total_amount),
+                best = cummax(total_amount),
+                worst = cummin(total_amount),
+                prod = cumprod(passenger_count),
+                change = round(diff(total_amount), 2),
+                pct = round(pct_change(total_amount), 3)
+            ) |
+            select(row, total_amount, prev, next2, running, best, worst, prod, change, pct) |
+            head(6)
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (6, 10)
+            row|total_amount|prev|next2|running|best|worst|prod|change|pct
+            u32|f64|f64|f64|f64|f64|f64|i64|f64|f64
+            ---
+            1|74.2|null|50.6|74.2|74.2|74.2|1|null|null
+            2|12.42|74.2|24.8|86.62|74.2|12.42|1|-61.78|-0.833
+            3|50.6|12.42|15.34|137.22|74.2|12.42|2|38.18|3.074
+            4|24.8|50.6|13.55|162.02|74.2|12.42|2|-25.8|-0.51
+            5|15.34|24.8|13.3|177.36|74.2|12.42|2|-9.46|-0.381
+            6|13.55|15.34|12.42|190.91|74.2|12.42|2|-1.79|-0.117
+            ---
+       "#
+        )
+    );
+
+    // Nulls are not filled, the change after a null is null.
+    let input = indoc! {r#"
+        parquet("tests/data/lists.parquet") |
+            select(shape_id, ints) |
+            unnest(ints) |
+            mutate(pct = round(pct_change(ints), 3)) |
+            head(8)
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (8, 3)
+            shape_id|ints|pct
+            u32|u32|f64
+            ---
+            1|3|null
+            1|88|28.333
+            1|94|0.068
+            2|73|-0.223
+            3|null|null
+            4|43|null
+            4|97|1.256
+            5|null|null
+            ---
+       "#
+        )
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            select(passenger_count, total_amount) |
+            mutate(
+                avg = rank(passenger_count),
+                min = rank(passenger_count, "min"),
+                max = rank(passenger_count, "max"),
+                ord = rank(passenger_count, "ordinal"),
+                dense = dense_rank(passenger_count),
+                pct = round(percent_rank(total_amount), 3),
+                tile = ntile(total_amount, 4)
+            ) |
+            arrange(total_amount) |
+            head(8)
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (8, 9)
+            passenger_count|total_amount|avg|min|max|ord|dense|pct|tile
+            i64|f64|f64|u32|u32|u32|u32|f64|u32
+            ---
+            2|-8.3|198.0|184|212|209|3|0.0|1
+            1|3.3|92.5|2|183|16|2|0.004|1
+            1|7.3|92.5|2|183|18|2|0.008|1
+            4|7.8|227.5|225|230|226|5|0.012|1
+            6|8.3|241.0|241|241|241|7|0.016|1
+            1|8.5|92.5|2|183|27|2|0.02|1
+            1|8.76|92.5|2|183|50|2|0.024|1
+            5|8.8|235.5|231|240|239|6|0.028|1
+            ---
+       "#
+        )
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            mutate(tile = ntile(total_amount, 4)) |
+            count(tile) |
+            show()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (4, 2)
+            tile|n
+            u32|u32
+            ---
+            1|63
+            2|63
+            3|62
+            4|62
+            ---
+       "#
+        )
+    );
+
+    Ok(())
+}
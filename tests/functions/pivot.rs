@@ -0,0 +1,166 @@
+// Copyright (C) 2023 Vince Vasta
+// SPDX-License-Identifier: Apache-2.0
+use anyhow::Result;
+use indoc::indoc;
+
+use super::assert_interpreter;
+
+#[test]
+fn pivot_longer() -> Result<()> {
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            slice(1, 2) |
+            select(VendorID, ends_with("amount")) |
+            pivot_longer(ends_with("amount"), names_to = kind, values_to = amount) |
+            show()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (8, 3)
+            VendorID|kind|amount
+            i64|str|f64
+            ---
+            2|fare_amount|14.5
+            2|fare_amount|6.5
+            2|tip_amount|3.76
+            2|tip_amount|0.0
+            2|tolls_amount|0.0
+            2|tolls_amount|0.0
+            2|total_amount|22.56
+            2|total_amount|9.8
+            ---
+       "#
+        )
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            slice(1, 2) |
+            select(VendorID, passenger_count, total_amount) |
+            pivot_longer(!contains("Vendor")) |
+            show()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (4, 3)
+            VendorID|name|value
+            i64|str|f64
+            ---
+            2|passenger_count|1.0
+            2|passenger_count|2.0
+            2|total_amount|22.56
+            2|total_amount|9.8
+            ---
+       "#
+        )
+    );
+
+    Ok(())
+}
+
+#[test]
+fn pivot_wider() -> Result<()> {
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            count(VendorID, payment_type) |
+            arrange(VendorID, payment_type) |
+            pivot_wider(names_from = payment_type, values_from = n, values_fill = 0) |
+            show()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (2, 6)
+            VendorID|Cash|Credit card|No charge|Unknown|Dispute
+            i64|u32|u32|u32|u32|u32
+            ---
+            1|12|37|1|5|0
+            2|41|148|0|4|2
+            ---
+       "#
+        )
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            select(VendorID, payment_type, total_amount) |
+            pivot_wider(names_from = payment_type, values_from = total_amount, values_fn = max) |
+            arrange(VendorID) |
+            show()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (2, 6)
+            VendorID|Credit card|Cash|Dispute|Unknown|No charge
+            i64|f64|f64|f64|f64|f64
+            ---
+            1|74.2|61.85|null|22.72|8.8
+            2|84.36|63.1|7.3|54.47|null
+            ---
+       "#
+        )
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            count(VendorID, payment_type) |
+            arrange(VendorID, payment_type) |
+            pivot_wider(names_from = payment_type, values_from = n) |
+            pivot_longer(!contains("Vendor"), names_to = payment_type, values_to = n) |
+            drop_nulls() |
+            arrange(VendorID, desc(n)) |
+            head(4)
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (4, 3)
+            VendorID|payment_type|n
+            i64|str|u32
+            ---
+            1|Credit card|37
+            1|Cash|12
+            1|Unknown|5
+            1|No charge|1
+            ---
+       "#
+        )
+    );
+
+    // All columns are used by names_from and values_from.
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            count(payment_type) |
+            pivot_wider(names_from = payment_type, values_from = n) |
+            show()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (1, 5)
+            Cash|Credit card|Dispute|No charge|Unknown
+            u32|u32|u32|u32|u32
+            ---
+            53|185|2|1|9
+            ---
+       "#
+        )
+    );
+
+    Ok(())
+}
@@ -0,0 +1,112 @@
+// Copyright (C) 2023 Vince Vasta
+// SPDX-License-Identifier: Apache-2.0
+use anyhow::Result;
+use indoc::indoc;
+
+use super::assert_interpreter;
+
+#[test]
+fn separate() -> Result<()> {
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            select(tpep_pickup_datetime, total_amount) |
+            mutate(pickup = as_str(tpep_pickup_datetime)) |
+            separate(pickup, into = (year, month, day), sep = "[- ]") |
+            head(3)
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (3, 5)
+            tpep_pickup_datetime|total_amount|year|month|day
+            datetime[ns]|f64|str|str|str
+            ---
+            2022-11-22 19:27:01|22.56|2022|11|22
+            2022-11-27 16:43:26|9.8|2022|11|27
+            2022-11-12 16:58:37|17.76|2022|11|12
+            ---
+       "#
+        )
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            distinct(payment_type) |
+            separate(payment_type, into = (first, second), remove = false) |
+            arrange(payment_type) |
+            show()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (5, 3)
+            payment_type|first|second
+            str|str|str
+            ---
+            Cash|Cash|null
+            Credit card|Credit|card
+            Dispute|Dispute|null
+            No charge|No|charge
+            Unknown|Unknown|null
+            ---
+       "#
+        )
+    );
+
+    Ok(())
+}
+
+#[test]
+fn unite() -> Result<()> {
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            select(VendorID, payment_type, total_amount) |
+            unite(id, payment_type, VendorID) |
+            head(3)
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (3, 2)
+            id|total_amount
+            str|f64
+            ---
+            Credit card_2|22.56
+            Cash_2|9.8
+            Credit card_2|17.76
+            ---
+       "#
+        )
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            select(VendorID, payment_type, total_amount) |
+            unite(id, VendorID, payment_type, sep = "/", remove = false) |
+            head(3)
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (3, 4)
+            id|VendorID|payment_type|total_amount
+            str|i64|str|f64
+            ---
+            2/Credit card|2|Credit card|22.56
+            2/Cash|2|Cash|9.8
+            2/Credit card|2|Credit card|17.76
+            ---
+       "#
+        )
+    );
+
+    Ok(())
+}
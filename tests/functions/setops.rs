@@ -0,0 +1,92 @@
+// Copyright (C) 2023 Vince Vasta
+// SPDX-License-Identifier: Apache-2.0
+use anyhow::Result;
+use indoc::indoc;
+
+use super::assert_interpreter;
+
+#[test]
+fn set_union() -> Result<()> {
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            select(payment_type, VendorID) |
+            slice(1, 6) |
+            first_df
+
+        parquet("tests/data/nyctaxi.parquet") |
+            select(VendorID, payment_type) |
+            slice(4, 6) |
+            second_df
+
+        first_df | union(second_df) | show()
+        first_df | union_all(second_df) | count(payment_type, VendorID, sort = true) | show()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (3, 2)
+            payment_type|VendorID
+            str|i64
+            ---
+            Credit card|2
+            Cash|2
+            Credit card|1
+            ---
+            shape: (3, 3)
+            payment_type|VendorID|n
+            str|i64|u32
+            ---
+            Credit card|2|6
+            Cash|2|4
+            Credit card|1|2
+            ---
+       "#
+        )
+    );
+
+    Ok(())
+}
+
+#[test]
+fn set_intersect_setdiff() -> Result<()> {
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            select(payment_type, passenger_count) |
+            filter(payment_type == "Unknown" | payment_type == "Dispute") |
+            first_df
+
+        parquet("tests/data/nyctaxi.parquet") |
+            select(payment_type, passenger_count) |
+            filter(payment_type == "Unknown") |
+            slice(1, 1) |
+            second_df
+
+        first_df | intersect(second_df) | show()
+        first_df | setdiff(second_df) | arrange(payment_type, passenger_count) | show()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (1, 2)
+            payment_type|passenger_count
+            str|i64
+            ---
+            Unknown|null
+            ---
+            shape: (2, 2)
+            payment_type|passenger_count
+            str|i64
+            ---
+            Dispute|1
+            Dispute|2
+            ---
+       "#
+        )
+    );
+
+    Ok(())
+}
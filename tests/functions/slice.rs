@@ -0,0 +1,311 @@
+// Copyright (C) 2023 Vince Vasta
+// SPDX-License-Identifier: Apache-2.0
+use anyhow::Result;
+use indoc::indoc;
+
+use super::assert_interpreter;
+
+#[test]
+fn slice_tail() -> Result<()> {
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            select(payment_type, total_amount) |
+            tail(3) |
+            show()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (3, 2)
+            payment_type|total_amount
+            str|f64
+            ---
+            Credit card|15.36
+            Credit card|17.76
+            Credit card|17.3
+            ---
+       "#
+        )
+    );
+
+    Ok(())
+}
+
+#[test]
+fn slice() -> Result<()> {
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            select(payment_type, total_amount) |
+            slice(2, 3) |
+            show()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (3, 2)
+            payment_type|total_amount
+            str|f64
+            ---
+            Cash|9.8
+            Credit card|17.76
+            Credit card|26.16
+            ---
+       "#
+        )
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            select(payment_type, total_amount) |
+            group_by(payment_type) |
+            tail(2) |
+            slice(2, 1) |
+            ungroup() |
+            arrange(payment_type) |
+            show()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (4, 2)
+            payment_type|total_amount
+            str|f64
+            ---
+            Cash|11.05
+            Credit card|17.3
+            Dispute|-8.3
+            Unknown|18.17
+            ---
+       "#
+        )
+    );
+
+    Ok(())
+}
+
+#[test]
+fn slice_min_max() -> Result<()> {
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            select(payment_type, total_amount) |
+            slice_min(total_amount, 3) |
+            show()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (3, 2)
+            payment_type|total_amount
+            str|f64
+            ---
+            Dispute|-8.3
+            Cash|3.3
+            Dispute|7.3
+            ---
+       "#
+        )
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            select(payment_type, total_amount) |
+            group_by(payment_type) |
+            slice_max(total_amount, 2) |
+            ungroup() |
+            show()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (10, 2)
+            payment_type|total_amount
+            str|f64
+            ---
+            Credit card|84.36
+            Credit card|77.64
+            Cash|63.1
+            Cash|61.85
+            Cash|61.85
+            Dispute|7.3
+            Dispute|-8.3
+            Unknown|54.47
+            Unknown|45.5
+            No charge|8.8
+            ---
+       "#
+        )
+    );
+
+    // Rows are sorted within each group and groups keep their input order.
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            select(payment_type, total_amount) |
+            group_by(payment_type) |
+            slice_min(total_amount, n = 2) |
+            ungroup() |
+            show()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (9, 2)
+            payment_type|total_amount
+            str|f64
+            ---
+            Credit card|8.5
+            Credit card|8.76
+            Cash|3.3
+            Cash|7.8
+            Dispute|-8.3
+            Dispute|7.3
+            Unknown|9.96
+            Unknown|17.28
+            No charge|8.8
+            ---
+       "#
+        )
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            select(payment_type, total_amount) |
+            slice_max(total_amount, n = 2) |
+            show()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (2, 2)
+            payment_type|total_amount
+            str|f64
+            ---
+            Credit card|84.36
+            Credit card|77.64
+            ---
+       "#
+        )
+    );
+
+    Ok(())
+}
+
+#[test]
+fn slice_sample() -> Result<()> {
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            group_by(payment_type) |
+            slice_sample(3, seed = 42) |
+            summarize(n = n()) |
+            arrange(payment_type) |
+            show()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (5, 2)
+            payment_type|n
+            str|u32
+            ---
+            Cash|3
+            Credit card|3
+            Dispute|2
+            No charge|1
+            Unknown|3
+            ---
+       "#
+        )
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            group_by(payment_type) |
+            slice_sample(3, seed = 42, replace = true) |
+            summarize(n = n()) |
+            arrange(payment_type) |
+            show()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (5, 2)
+            payment_type|n
+            str|u32
+            ---
+            Cash|3
+            Credit card|3
+            Dispute|3
+            No charge|3
+            Unknown|3
+            ---
+       "#
+        )
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            group_by(payment_type) |
+            slice_sample(n = 1, seed = 1) |
+            summarize(n = n()) |
+            arrange(payment_type) |
+            show()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (5, 2)
+            payment_type|n
+            str|u32
+            ---
+            Cash|1
+            Credit card|1
+            Dispute|1
+            No charge|1
+            Unknown|1
+            ---
+       "#
+        )
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            slice_sample(prop = 0.1) |
+            count() |
+            show()
+    "#};
+
+    assert_interpreter!(
+        input,
+        indoc!(
+            r#"
+            shape: (1, 1)
+            n
+            u32
+            ---
+            25
+            ---
+       "#
+        )
+    );
+
+    Ok(())
+}
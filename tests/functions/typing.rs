@@ -0,0 +1,746 @@
+// Copyright (C) 2023 Vince Vasta
+// SPDX-License-Identifier: Apache-2.0
+use anyhow::Result;
+use indoc::indoc;
+
+use super::assert_interpreter;
+
+macro_rules! assert_error {
+    ($input:expr, $expected:expr) => {
+        match dply::interpreter::eval_to_string($input) {
+            Ok(_) => panic!("Expected error for:\n{}", $input),
+            Err(e) => assert_eq!(e.to_string(), $expected),
+        }
+    };
+}
+
+#[test]
+fn mutate_types() {
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            mutate(avg_type = mean(payment_type)) |
+            show()
+    "#};
+    assert_error!(
+        input,
+        "mutate error: cannot compute `mean` of column 'payment_type' with type str"
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            mutate(double_type = payment_type * 2) |
+            show()
+    "#};
+    assert_error!(
+        input,
+        "mutate error: cannot apply '*' to 'payment_type' (str) and '2' (f64)"
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            mutate(pickup = ymd_hms(tpep_pickup_datetime)) |
+            show()
+    "#};
+    assert_error!(
+        input,
+        "mutate error: `ymd_hms` column 'tpep_pickup_datetime' must be a string, found datetime[ns]"
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            mutate(code = to_upper(total_amount)) |
+            show()
+    "#};
+    assert_error!(
+        input,
+        "mutate error: `to_upper` column 'total_amount' must be a string, found f64"
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            mutate(flag = as_int(store_and_fwd_flag)) |
+            show()
+    "#};
+    assert_error!(
+        input,
+        "conversion from `str` to `i64` failed in column 'store_and_fwd_flag' for 241 out of 250 values: [\"N\", \"N\", \"N\", \"N\", \"N\", … \"N\"]"
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            mutate(flag = as_bool(store_and_fwd_flag)) |
+            show()
+    "#};
+    assert_error!(
+        input,
+        "conversion from `str` to `bool` failed for value \"N\""
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            mutate(flag = as_int(store_and_fwd_flag, strict = ture)) |
+            show()
+    "#};
+    assert_error!(
+        input,
+        "Invalid argument 'as_int(store_and_fwd_flag, strict = ture)' for function 'mutate'"
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            mutate(amount = round(total_amount, 1.7)) |
+            show()
+    "#};
+    assert_error!(
+        input,
+        "mutate error: `round` digits must be a single non-negative integer"
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            mutate(previous = lag(total_amount, 1.5)) |
+            show()
+    "#};
+    assert_error!(
+        input,
+        "mutate error: `lag` offset must be a single non-negative integer"
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            mutate(year = year(passenger_count)) |
+            show()
+    "#};
+    assert_error!(
+        input,
+        "mutate error: `year` column 'passenger_count' must be a date or datetime, found i64"
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            mutate(hour = floor_date(tpep_pickup_datetime, "1x")) |
+            show()
+    "#};
+    assert_error!(
+        input,
+        "mutate error: `floor_date` invalid unit '1x', expected a duration like 15m, 1h, 1d, or 1mo"
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            mutate(years = date_diff(tpep_pickup_datetime, tpep_dropoff_datetime, "years")) |
+            show()
+    "#};
+    assert_error!(
+        input,
+        "mutate error: `date_diff` invalid unit 'years', expected days, hours, minutes, seconds, or millis"
+    );
+}
+
+#[test]
+fn filter_types() {
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            filter(tpep_pickup_datetime > 5) |
+            show()
+    "#};
+    assert_error!(
+        input,
+        "filter error: cannot compare 'tpep_pickup_datetime' (datetime[ns]) with '5' (f64)"
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            filter(payment_type == "Cash" & passenger_count == "two") |
+            show()
+    "#};
+    assert_error!(
+        input,
+        "filter error: cannot compare 'passenger_count' (i64) with '\"two\"' (str)"
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            filter(payment_type %in% ("Cash", 2)) |
+            show()
+    "#};
+    assert_error!(
+        input,
+        "filter error: cannot compare 'payment_type' (str) with '2' (f64)"
+    );
+
+    let input = indoc! {r#"
+        csv("tests/data/zones.csv") | zones_df
+        parquet("tests/data/nyctaxi.parquet") |
+            filter(is_in(PULocationID, zones_df.Zone)) |
+            show()
+    "#};
+    assert_error!(
+        input,
+        "filter error: cannot compare 'PULocationID' (i64) with 'zones_df.Zone' (str)"
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            filter(between(tpep_pickup_datetime, 1, 2)) |
+            show()
+    "#};
+    assert_error!(
+        input,
+        "filter error: cannot compare 'tpep_pickup_datetime' (datetime[ns]) with '1' (f64)"
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            filter(to_upper(payment_type) + 1 > 2) |
+            show()
+    "#};
+    assert_error!(
+        input,
+        "filter error: cannot apply '+' to 'to_upper(payment_type)' (str) and '1' (f64)"
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            filter(starts_with(fare_amount, "1")) |
+            show()
+    "#};
+    assert_error!(
+        input,
+        "filter error: `starts_with` column 'fare_amount' must be a string, found f64"
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            filter(contains(payment_type, "a", "b")) |
+            show()
+    "#};
+    assert_error!(
+        input,
+        "filter error: `contains` expects one pattern argument"
+    );
+}
+
+#[test]
+fn summarize_types() {
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            group_by(payment_type) |
+            summarize(rate_sd = sd(rate_code)) |
+            show()
+    "#};
+    assert_error!(
+        input,
+        "summarize error: cannot compute `sd` of column 'rate_code' with type str"
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            summarize(any_fare = any(fare_amount)) |
+            show()
+    "#};
+    assert_error!(
+        input,
+        "summarize error: cannot compute `any` of column 'fare_amount' with type f64"
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            summarize(cor = cor(fare_amount, payment_type)) |
+            show()
+    "#};
+    assert_error!(
+        input,
+        "summarize error: cannot compute `cor` of column 'payment_type' with type str"
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            summarize(total = sum(payment_type + 1)) |
+            show()
+    "#};
+    assert_error!(
+        input,
+        "summarize error: cannot apply '+' to 'payment_type' (str) and '1' (f64)"
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            summarize(total = sum(to_upper(payment_type))) |
+            show()
+    "#};
+    assert_error!(
+        input,
+        "summarize error: cannot compute `sum` of column 'to_upper(payment_type)' with type str"
+    );
+}
+
+#[test]
+fn check_before_eval() -> Result<()> {
+    // The error in the second pipeline is reported before writing the file.
+    let path = std::env::temp_dir().join("dply_check_before_eval.csv");
+    let _ = std::fs::remove_file(&path);
+
+    let input = format!(
+        indoc! {r#"
+            parquet("tests/data/nyctaxi.parquet") |
+                select(payment_type, total_amount) |
+                csv("{}")
+
+            parquet("tests/data/nyctaxi.parquet") |
+                mutate(total = total_amount + payment_type) |
+                show()
+        "#},
+        path.display()
+    );
+    assert_error!(
+        &input,
+        "mutate error: cannot apply '+' to 'total_amount' (f64) and 'payment_type' (str)"
+    );
+    assert!(!path.exists());
+
+    // Files written by a pipeline can be read by the following pipelines.
+    let input = format!(
+        indoc! {r#"
+            parquet("tests/data/nyctaxi.parquet") |
+                select(payment_type, total_amount) |
+                csv("{0}")
+
+            csv("{0}") |
+                count(payment_type) |
+                show()
+        "#},
+        path.display()
+    );
+    assert_interpreter!(
+        input.as_str(),
+        indoc!(
+            r#"
+            shape: (5, 2)
+            payment_type|n
+            str|u32
+            ---
+            Cash|53
+            Credit card|185
+            Dispute|2
+            No charge|1
+            Unknown|9
+            ---
+        "#
+        )
+    );
+
+    std::fs::remove_file(&path)?;
+
+    Ok(())
+}
+
+#[test]
+fn check_after_pivot_wider() -> Result<()> {
+    // Pipelines after a pivot_wider are still checked before writing the file.
+    let path = std::env::temp_dir().join("dply_check_after_pivot_wider.csv");
+    let _ = std::fs::remove_file(&path);
+
+    let input = format!(
+        indoc! {r#"
+            parquet("tests/data/nyctaxi.parquet") |
+                pivot_wider(names_from = payment_type, values_from = total_amount) |
+                show()
+
+            parquet("tests/data/nyctaxi.parquet") |
+                select(payment_type, total_amount) |
+                csv("{}")
+
+            parquet("tests/data/nyctaxi.parquet") |
+                filter(amout > 1) |
+                show()
+        "#},
+        path.display()
+    );
+    assert_error!(
+        &input,
+        "filter error: Unknown column 'amout', did you mean 'tip_amount'?"
+    );
+    assert!(!path.exists());
+
+    Ok(())
+}
+
+#[test]
+fn did_you_mean() {
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            fitler(total_amount > 10) |
+            show()
+    "#};
+    assert_error!(input, "Unknown function: fitler, did you mean 'filter'?");
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            mutate(avg_amount = maen(total_amount)) |
+            show()
+    "#};
+    assert_error!(
+        input,
+        "Unknown function 'maen' for function 'mutate', did you mean 'mean'?"
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            csv("trips.csv", overwite = true)
+    "#};
+    assert_error!(
+        input,
+        "Unknown argument 'overwite' for function 'csv', did you mean 'overwrite'?"
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            select(total_amout) |
+            show()
+    "#};
+    assert_error!(
+        input,
+        "select error: Unknown column 'total_amout', did you mean 'total_amount'?"
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            filter(total_amout > 10) |
+            show()
+    "#};
+    assert_error!(
+        input,
+        "filter error: Unknown column 'total_amout', did you mean 'total_amount'?"
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            pivot_wider(names_from = payment_type, values_from = total_amout) |
+            show()
+    "#};
+    assert_error!(
+        input,
+        "pivot_wider error: Unknown column 'total_amout', did you mean 'total_amount'?"
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") | trips_df
+        trip_df | show()
+    "#};
+    assert_error!(
+        input,
+        "Undefined variable 'trip_df', did you mean 'trips_df'?"
+    );
+}
+
+#[test]
+fn bind_types() {
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") | select(payment_type) | types_df
+        parquet("tests/data/nyctaxi.parquet") | select(payment_type = VendorID) | ids_df
+        types_df | bind_rows(ids_df) | show()
+    "#};
+    assert_error!(
+        input,
+        "bind_rows error: column 'payment_type' has incompatible types str and i64"
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") | select(payment_type) | types_df
+        types_df | bind_cols(types_df) | show()
+    "#};
+    assert_error!(input, "bind_cols error: duplicate column 'payment_type'");
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") | select(payment_type) | slice(1, 3) | types_df
+        parquet("tests/data/nyctaxi.parquet") | select(VendorID) | slice(1, 2) | ids_df
+        types_df | bind_cols(ids_df) | show()
+    "#};
+    assert_error!(
+        input,
+        "bind_cols error: dataframes have different number of rows 3 and 2"
+    );
+}
+
+#[test]
+fn setops_types() {
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") | select(payment_type, VendorID) | types_df
+        parquet("tests/data/nyctaxi.parquet") | select(payment_type = VendorID, VendorID) | ids_df
+        types_df | union(ids_df) | show()
+    "#};
+    assert_error!(
+        input,
+        "union error: column 'payment_type' has incompatible types str and i64"
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") | select(payment_type, VendorID) | types_df
+        parquet("tests/data/nyctaxi.parquet") | select(payment_type) | ids_df
+        types_df | setdiff(ids_df) | show()
+    "#};
+    assert_error!(
+        input,
+        "setdiff error: missing columns in 'ids_df': 'VendorID'"
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") | select(payment_type, VendorID) | types_df
+        parquet("tests/data/nyctaxi.parquet") | select(payment_type) | ids_df
+        ids_df | intersect(types_df) | show()
+    "#};
+    assert_error!(
+        input,
+        "intersect error: missing columns in the input: 'VendorID'"
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") | select(payment_type, VendorID) | types_df
+        parquet("tests/data/nyctaxi.parquet") | select(payment_type, total_amount) | amounts_df
+        types_df | union(amounts_df) | show()
+    "#};
+    assert_error!(
+        input,
+        "union error: missing columns in 'amounts_df': 'VendorID'; missing columns in the input: 'total_amount'"
+    );
+}
+
+#[test]
+fn join_validate() {
+    let input = indoc! {r#"
+        csv("tests/data/zones.csv") | zones_df
+        parquet("tests/data/nyctaxi.parquet") |
+            left_join(zones_df, PULocationID == LocationID, validate = "1:1") |
+            show()
+    "#};
+    assert_error!(
+        input,
+        "join error: duplicate keys in the input are not allowed with 1:1 validation"
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") | select(PULocationID) | trips_df
+        csv("tests/data/zones.csv") |
+            left_join(trips_df, LocationID == PULocationID, validate = "m:1") |
+            show()
+    "#};
+    assert_error!(
+        input,
+        "join error: duplicate keys in trips_df are not allowed with m:1 validation"
+    );
+
+    let input = indoc! {r#"
+        csv("tests/data/zones.csv") | zones_df
+        parquet("tests/data/nyctaxi.parquet") |
+            semi_join(zones_df, PULocationID == LocationID, validate = "m:1") |
+            show()
+    "#};
+    assert_error!(
+        input,
+        "join error: m:1 validation is only supported by inner, left, and outer joins"
+    );
+
+    let input = indoc! {r#"
+        csv("tests/data/zones.csv") | zones_df
+        parquet("tests/data/nyctaxi.parquet") |
+            left_join(zones_df, PULocationID == LocationID, validate = "one") |
+            show()
+    "#};
+    assert_error!(
+        input,
+        "join error: invalid validate value 'one', expected one of 1:1, 1:m, m:1, m:m"
+    );
+}
+
+#[test]
+fn join_conditions() {
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") | select(amount = total_amount) | amounts_df
+        parquet("tests/data/nyctaxi.parquet") |
+            inner_join(amounts_df, payment_type < amount) |
+            show()
+    "#};
+    assert_error!(
+        input,
+        "join error: cannot compare 'payment_type' (str) with 'amount' (f64)"
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") | select(amount = total_amount) | amounts_df
+        parquet("tests/data/nyctaxi.parquet") |
+            inner_join(amounts_df, total_amount != amount) |
+            show()
+    "#};
+    assert_error!(
+        input,
+        "join error: unsupported join condition 'total_amount != amount'"
+    );
+}
+
+#[test]
+fn asof_join_types() {
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") | select(pickup = tpep_pickup_datetime) | trips_df
+        parquet("tests/data/nyctaxi.parquet") |
+            asof_join(trips_df, tpep_pickup_datetime == pickup, strategy = "closest") |
+            show()
+    "#};
+    assert_error!(
+        input,
+        "asof_join error: invalid strategy 'closest', expected one of backward, forward, nearest"
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") | select(pickup = tpep_pickup_datetime) | trips_df
+        parquet("tests/data/nyctaxi.parquet") |
+            asof_join(trips_df, tpep_pickup_datetime == pickup, tolerance = "5 minutes") |
+            show()
+    "#};
+    assert_error!(
+        input,
+        "asof_join error: invalid tolerance '5 minutes', expected a duration like 30s, 5m, 1h30m, or 2d"
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") | select(pickup = tpep_pickup_datetime) | trips_df
+        parquet("tests/data/nyctaxi.parquet") |
+            asof_join(trips_df, total_amount == pickup) |
+            show()
+    "#};
+    assert_error!(
+        input,
+        "asof_join error: 'total_amount' and 'pickup' don't have the same type"
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") | select(pickup = tpep_pickup_datetime) | trips_df
+        parquet("tests/data/nyctaxi.parquet") |
+            asof_join(trips_df, tpep_pickup_datetime == pickup, tolerence = 10) |
+            show()
+    "#};
+    assert_error!(
+        input,
+        "Unknown argument 'tolerence' for function 'asof_join', did you mean 'tolerance'?"
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") | select(pickup = tpep_pickup_datetime) | trips_df
+        parquet("tests/data/nyctaxi.parquet") |
+            asof_join(trips_df, pickup = pickup) |
+            show()
+    "#};
+    assert_error!(
+        input,
+        "Invalid argument 'pickup = pickup' for function 'asof_join'"
+    );
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") | select(LocationID = DOLocationID) | zones_df
+        parquet("tests/data/nyctaxi.parquet") |
+            asof_join(zones_df, PULocationID == LocationID, tolerance = 1.5) |
+            show()
+    "#};
+    assert_error!(
+        input,
+        "asof_join error: invalid tolerance 1.5 for 'PULocationID', expected a non-negative integer"
+    );
+}
+
+#[test]
+fn slice_types() {
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            slice_sample(n = 1, prop = 0.1) |
+            show()
+    "#};
+    assert_error!(input, "slice_sample error: use either n or prop");
+
+    let input = indoc! {r#"
+        parquet("tests/data/nyctaxi.parquet") |
+            slice_max(total_amount, n = -1) |
+            show()
+    "#};
+    assert_error!(input, "slice_max error: number of rows must be >= 0");
+}
+
+#[test]
+fn error_kinds() {
+    let kind = |input| dply::interpreter::eval_to_string(input).map_err(|e| e.kind());
+
+    assert_eq!(kind("parquet(\"a.parquet\" | show()"), Err("parse"));
+    assert_eq!(kind("parquet(1) | show()"), Err("type"));
+    assert_eq!(
+        kind("parquet(\"tests/data/nyctaxi.parquet\") | select(amount)"),
+        Err("schema")
+    );
+    assert_eq!(
+        kind("parquet(\"tests/data/missing.parquet\") | show()"),
+        Err("io")
+    );
+    assert_eq!(
+        kind("parquet(\"tests/data/nyctaxi.parquet\") | group_by(payment_type) | count()"),
+        Err("type")
+    );
+    assert_eq!(
+        kind(
+            "parquet(\"tests/data/nyctaxi.parquet\") | mutate(ts = ymd_hms(payment_type)) | show()"
+        ),
+        Err("engine")
+    );
+    assert_eq!(
+        kind(
+            "parquet(\"tests/data/nyctaxi.parquet\") | mutate(flag = as_int(store_and_fwd_flag)) | show()"
+        ),
+        Err("engine")
+    );
+
+    let input = r#"csv("tests/data/missing.csv") | show()"#;
+    assert_eq!(kind(input), Err("io"));
+    assert_error!(
+        input,
+        "No such file or directory (os error 2): tests/data/missing.csv: 'csv scan' failed"
+    );
+}
+
+#[test]
+fn error_location() {
+    let input = indoc! {r#"
+        # Comments lines are counted.
+        parquet("tests/data/nyctaxi.parquet") |
+            select(payment_type) |
+            mutate(x = payment_type * 2) |
+            show()
+    "#};
+
+    let e = dply::interpreter::eval_to_string(input).unwrap_err();
+    let location = e.location().unwrap();
+    assert_eq!((location.line, location.column), (4, 5));
+    assert_eq!(e.function(), Some("mutate"));
+
+    let e = dply::interpreter::eval_to_string("parquet(\"a.parquet\") |\n  show(").unwrap_err();
+    let location = e.location().unwrap();
+    assert_eq!((location.line, location.column), (2, 8));
+    assert_eq!(e.function(), None);
+}
+
+#[test]
+fn error_format_json() {
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_dply"))
+        .args(["--error-format", "json", "-c"])
+        .arg("parquet(\"tests/data/nyctaxi.parquet\") | select(amount)")
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr).trim(),
+        r#"{"column":41,"file":null,"function":"select","kind":"schema","line":1,"message":"select error: Unknown column 'amount', did you mean 'tip_amount'?"}"#
+    );
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_dply"))
+        .args(["-c", "parquet(\"tests/data/missing.parquet\") | show()"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(4));
+}